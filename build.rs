@@ -1,4 +1,10 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+// The status words table, shared with the application
+#[macro_use]
+#[allow(dead_code)]
+#[path = "src/status_words.rs"]
+mod status_words;
 
 /// Decode a GIF's first frame into an 8-bit grayscale (luma) buffer, row-major.
 /// Reproduces `image`'s `into_luma8()`: luma = (2126*R + 7152*G + 722*B) / 10000.
@@ -32,8 +38,26 @@ fn gif_to_luma(path: &std::path::Path) -> (u32, u32, Vec<u8>) {
     (width, height, luma)
 }
 
+/// Generates the status words of the Python client in `OUT_DIR`. The client module is committed,
+/// the build fails when it is out of date.
+fn write_python_status_words() {
+    let committed = Path::new("tests/application_client/boilerplate_status_words.py");
+    let generated =
+        PathBuf::from(std::env::var("OUT_DIR").unwrap()).join(committed.file_name().unwrap());
+    let source = status_words!(python_client);
+    std::fs::write(&generated, source).unwrap();
+    assert!(
+        std::fs::read_to_string(committed).ok().as_deref() == Some(source),
+        "{} is out of date, replace it with {}",
+        committed.display(),
+        generated.display()
+    );
+}
+
 fn main() {
     println!("cargo:rerun-if-changed=script.ld");
+    println!("cargo:rerun-if-changed=src/status_words.rs");
+    println!("cargo:rerun-if-changed=tests/application_client/boilerplate_status_words.py");
     println!("cargo:rerun-if-changed=icons/crab_14x14.gif");
     println!("cargo:rerun-if-changed=icons/mask_14x14.gif");
 
@@ -62,4 +86,6 @@ fn main() {
         .unwrap()
         .write_image_data(&gray)
         .unwrap();
+
+    write_python_status_words();
 }
@@ -0,0 +1,118 @@
+"""Generate the seed corpora of the fuzz targets.
+
+The seeds are built from the test vectors of tests/standalone/test_sign_cmd.py: the derivation
+paths and transactions it sends, the headers of the SIGN_TX APDUs carrying them, and the swap
+parameters that would match them.
+
+Usage: python3 fuzz/seed_corpus.py
+"""
+
+import ast
+import json
+from pathlib import Path
+from typing import List, Tuple
+
+ROOT = Path(__file__).resolve().parent.parent
+TEST_VECTORS = ROOT / "tests" / "standalone" / "test_sign_cmd.py"
+CORPUS = Path(__file__).resolve().parent / "corpus"
+
+# Values of tests/application_client/boilerplate_command_sender.py
+CLA = 0xE0
+INS_SIGN_TX = 0x06
+P2_LAST = 0x00
+P2_MORE = 0x80
+MAX_APDU_LEN = 255
+
+
+def pack_derivation_path(path: str) -> bytes:
+    components = path.split("/")[1:]
+    packed = bytes([len(components)])
+    for component in components:
+        value = int(component.rstrip("'"))
+        if component.endswith("'"):
+            value |= 0x80000000
+        packed += value.to_bytes(4, byteorder="big")
+    return packed
+
+
+def serialize_tx(fields: dict) -> bytes:
+    # Same encoding as application_client.boilerplate_transaction.Transaction.serialize()
+    tx = {key: fields[key] for key in ("nonce", "coin", "value", "to", "memo")}
+    if fields.get("token") is not None:
+        tx["token"] = fields["token"]
+    return json.dumps(tx).encode("utf-8")
+
+
+def read_test_vectors() -> Tuple[List[str], List[dict]]:
+    tree = ast.parse(TEST_VECTORS.read_text())
+    paths: List[str] = []
+    transactions: List[dict] = []
+    for node in ast.walk(tree):
+        if isinstance(node, ast.AnnAssign) and getattr(node.target, "id", "") == "path":
+            paths.append(ast.literal_eval(node.value))
+        if (
+            isinstance(node, ast.Call)
+            and getattr(node.func, "id", "") == "Transaction"
+        ):
+            try:
+                fields = {kw.arg: ast.literal_eval(kw.value) for kw in node.keywords}
+            except ValueError:
+                # Not built from literals only
+                continue
+            transactions.append(fields)
+    return sorted(set(paths)), transactions
+
+
+def pack_swap_input(path: str, fields: dict) -> bytes:
+    # Format of swap_sim::fuzz::check_swap_params
+    amount = int(fields["value"]).to_bytes(16, byteorder="big")
+    amount_len = (int(fields["value"]).bit_length() + 7) // 8
+    destination = fields["to"].encode()
+    # The length of the extra id is a single byte
+    memo = fields["memo"].encode()[:255]
+    return (
+        pack_derivation_path(path)
+        + amount
+        + bytes([amount_len])
+        + bytes(16)
+        + bytes([0])
+        + bytes([len(destination)])
+        + destination
+        + bytes([len(memo)])
+        + memo
+        + serialize_tx(fields)
+    )
+
+
+def write_seeds(target: str, seeds: List[bytes]) -> None:
+    directory = CORPUS / target
+    directory.mkdir(parents=True, exist_ok=True)
+    for old in directory.glob("seed_*"):
+        old.unlink()
+    for index, seed in enumerate(dict.fromkeys(seeds)):
+        (directory / f"seed_{index:03}").write_bytes(seed)
+
+
+def main() -> None:
+    paths, transactions = read_test_vectors()
+    serialized = [serialize_tx(fields) for fields in transactions]
+
+    headers = []
+    for tx in serialized:
+        chunks = (len(tx) + MAX_APDU_LEN - 1) // MAX_APDU_LEN
+        headers.append(bytes([CLA, INS_SIGN_TX, 0, P2_MORE]))
+        for index in range(1, chunks + 1):
+            p2 = P2_LAST if index == chunks else P2_MORE
+            headers.append(bytes([CLA, INS_SIGN_TX, index, p2]))
+
+    write_seeds("tx", serialized)
+    write_seeds("bip32_path", [pack_derivation_path(path) for path in paths])
+    write_seeds("instruction", headers)
+    write_seeds(
+        "check_swap_params",
+        [pack_swap_input(path, fields) for path in paths for fields in transactions],
+    )
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,204 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+//! Address book
+//!
+//! Labels of destination addresses saved by the user, stored in NVM. Entries are added with the
+//! ADD_ADDRESS_BOOK_ENTRY APDU and removed one by one with the REMOVE_ADDRESS_BOOK_ENTRY APDU,
+//! after confirmation on the device. They are displayed next to the destination during the
+//! transaction review.
+//!
+//! The address book is only used while the "Address book" setting is enabled. Disabling the
+//! setting erases every entry, see [`crate::app_ui::menu::apply_settings`]. This switch is the
+//! only removal available from the settings page: the NBGL settings page of the SDK only holds
+//! on/off switches, so it cannot list the entries to remove them one by one.
+
+use ledger_device_sdk::nvm::*;
+use ledger_device_sdk::NVMData;
+
+use crate::settings::{Settings, SETTING_ADDRESS_BOOK};
+use crate::AppSW;
+
+/// Maximum number of saved addresses.
+pub const ADDRESS_BOOK_ENTRIES: usize = 10;
+/// Maximum length of a label, in bytes.
+pub const MAX_LABEL_LEN: usize = 20;
+
+const ADDRESS_LEN: usize = 20;
+
+/// A saved address and its label.
+#[derive(Clone, Copy)]
+pub struct Contact {
+    address: [u8; ADDRESS_LEN],
+    label: [u8; MAX_LABEL_LEN],
+    /// Length of the label, 0 for an empty entry.
+    label_len: u8,
+}
+
+impl Contact {
+    pub fn address(&self) -> &[u8; ADDRESS_LEN] {
+        &self.address
+    }
+
+    pub fn label(&self) -> &str {
+        // Labels are checked to be printable ASCII when parsed
+        core::str::from_utf8(&self.label[..self.label_len as usize]).unwrap_or("")
+    }
+}
+
+impl TryFrom<&[u8]> for Contact {
+    type Error = AppSW;
+
+    /// Constructs a [`Contact`] from APDU-encoded bytes.
+    ///
+    /// # Format
+    ///
+    /// - 20 bytes: Address
+    /// - 1 byte: Label length (1 to [`MAX_LABEL_LEN`])
+    /// - Remaining bytes: Label, printable ASCII only
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() < ADDRESS_LEN + 1
+            || data[ADDRESS_LEN] as usize != data.len() - ADDRESS_LEN - 1
+        {
+            return Err(AppSW::WrongApduLength);
+        }
+
+        let label = &data[ADDRESS_LEN + 1..];
+        if label.is_empty()
+            || label.len() > MAX_LABEL_LEN
+            || !label.iter().all(|c| c.is_ascii_graphic() || *c == b' ')
+        {
+            return Err(AppSW::AddressBookEntryInvalid);
+        }
+
+        let mut contact = EMPTY_CONTACT;
+        contact.address.copy_from_slice(&data[..ADDRESS_LEN]);
+        contact.label[..label.len()].copy_from_slice(label);
+        contact.label_len = label.len() as u8;
+        Ok(contact)
+    }
+}
+
+const EMPTY_CONTACT: Contact = Contact {
+    address: [0u8; ADDRESS_LEN],
+    label: [0u8; MAX_LABEL_LEN],
+    label_len: 0,
+};
+
+const EMPTY_BOOK: [Contact; ADDRESS_BOOK_ENTRIES] = [EMPTY_CONTACT; ADDRESS_BOOK_ENTRIES];
+
+// This is necessary to store the object in NVM and not in RAM
+#[link_section = ".nvm_data"]
+static mut BOOK: NVMData<AtomicStorage<[Contact; ADDRESS_BOOK_ENTRIES]>> =
+    NVMData::new(AtomicStorage::new(&EMPTY_BOOK));
+
+#[derive(Clone, Copy, Default)]
+pub struct AddressBook;
+
+impl AddressBook {
+    /// Returns true if the address book is enabled in the settings.
+    pub fn enabled(&self) -> bool {
+        let settings: Settings = Default::default();
+        settings.get_element(SETTING_ADDRESS_BOOK) != 0
+    }
+
+    /// Returns the contact saved for `address`, if any.
+    pub fn contact(&self, address: &[u8; ADDRESS_LEN]) -> Option<&Contact> {
+        if !self.enabled() {
+            return None;
+        }
+        self.book()
+            .iter()
+            .find(|contact| contact.label_len != 0 && contact.address == *address)
+    }
+
+    /// Returns the label saved for `address`, if any.
+    pub fn find(&self, address: &[u8; ADDRESS_LEN]) -> Option<&str> {
+        self.contact(address).map(|contact| contact.label())
+    }
+
+    /// Returns the index of the entry where `contact` would be saved: the entry of the same
+    /// address, whose label is replaced, or else a free entry.
+    ///
+    /// Returns [`AppSW::AddressBookDisabled`] if the address book is disabled,
+    /// [`AppSW::AddressBookDuplicate`] if the label is already saved, for this or another
+    /// address, and [`AppSW::AddressBookFull`] if there is no free entry.
+    pub fn slot(&self, contact: &Contact) -> Result<usize, AppSW> {
+        if !self.enabled() {
+            return Err(AppSW::AddressBookDisabled);
+        }
+
+        let book = self.book();
+        if book
+            .iter()
+            .any(|entry| entry.label_len != 0 && entry.label() == contact.label())
+        {
+            return Err(AppSW::AddressBookDuplicate);
+        }
+        book.iter()
+            .position(|entry| entry.label_len != 0 && entry.address == contact.address)
+            .or_else(|| book.iter().position(|entry| entry.label_len == 0))
+            .ok_or(AppSW::AddressBookFull)
+    }
+
+    /// Saves a contact. The label of an already saved address is replaced. See [`Self::slot`]
+    /// for the errors.
+    pub fn add(&self, contact: &Contact) -> Result<(), AppSW> {
+        let index = self.slot(contact)?;
+        let mut book = *self.book();
+        book[index] = *contact;
+        self.update(&book);
+        Ok(())
+    }
+
+    /// Removes the contact saved for `address`.
+    pub fn remove(&self, address: &[u8; ADDRESS_LEN]) -> Result<(), AppSW> {
+        if !self.enabled() {
+            return Err(AppSW::AddressBookDisabled);
+        }
+
+        let mut book = *self.book();
+        let entry = book
+            .iter_mut()
+            .find(|entry| entry.label_len != 0 && entry.address == *address)
+            .ok_or(AppSW::AddressBookEntryNotFound)?;
+        *entry = EMPTY_CONTACT;
+        self.update(&book);
+        Ok(())
+    }
+
+    /// Erases every saved contact.
+    pub fn clear(&self) {
+        if self.book().iter().any(|contact| contact.label_len != 0) {
+            self.update(&EMPTY_BOOK);
+        }
+    }
+
+    fn book(&self) -> &[Contact; ADDRESS_BOOK_ENTRIES] {
+        let data = &raw const BOOK;
+        let storage = unsafe { (*data).get_ref() };
+        storage.get_ref()
+    }
+
+    fn update(&self, book: &[Contact; ADDRESS_BOOK_ENTRIES]) {
+        let data = &raw mut BOOK;
+        let storage = unsafe { (*data).get_mut() };
+        unsafe {
+            storage.update(book);
+        }
+    }
+}
@@ -15,33 +15,39 @@
  *  limitations under the License.
  *****************************************************************************/
 
+use super::FERRIS;
+use crate::strings::STRINGS;
 use crate::AppSW;
 use alloc::format;
 
-use ledger_device_sdk::include_gif;
 use ledger_device_sdk::io::Comm;
-use ledger_device_sdk::nbgl::{NbglAddressReview, NbglGlyph};
+use ledger_device_sdk::nbgl::NbglAddressReview;
 
 // Display only the last 20 bytes of the address
 const DISPLAY_ADDR_BYTES_LEN: usize = 20;
 
+/// Displays the address of a public key for verification and returns true if user approved it.
+///
+/// On touchscreen devices (Stax, Flex, Apex P), the NBGL address review offers a "Show as QR"
+/// page, which encodes the same string as the text screens, so that the address can be checked
+/// with a phone camera. Nano devices only show the text screens.
+///
+/// # Arguments
+///
+/// * `comm` - Reference to the communication object
+/// * `addr` - Address hash, whose last 20 bytes are the address
 pub fn ui_display_pk(comm: &mut Comm, addr: &[u8]) -> Result<bool, AppSW> {
-    let addr_hex = format!(
-        "0x{}",
-        hex::encode(&addr[addr.len() - DISPLAY_ADDR_BYTES_LEN..]).to_uppercase()
-    );
+    let displayed = addr
+        .len()
+        .checked_sub(DISPLAY_ADDR_BYTES_LEN)
+        .map(|start| &addr[start..])
+        .ok_or(AppSW::AddrDisplayFail)?;
+    let addr_hex = format!("0x{}", hex::encode(displayed).to_uppercase());
 
-    // Load glyph from file with include_gif macro. Creates an NBGL compatible glyph.
-    #[cfg(target_os = "apex_p")]
-    const FERRIS: NbglGlyph = NbglGlyph::from_include(include_gif!("glyphs/crab_48x48.png", NBGL));
-    #[cfg(any(target_os = "stax", target_os = "flex"))]
-    const FERRIS: NbglGlyph = NbglGlyph::from_include(include_gif!("glyphs/crab_64x64.gif", NBGL));
-    #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
-    const FERRIS: NbglGlyph = NbglGlyph::from_include(include_gif!("icons/crab_14x14.gif", NBGL));
-
-    // Display the address confirmation screen.
+    // Display the address confirmation screen. The QR code of touchscreen devices is built from
+    // `addr_hex` too: the string scanned is exactly the string displayed.
     Ok(NbglAddressReview::new()
         .glyph(&FERRIS)
-        .review_title("Verify CRAB address")
+        .review_title(STRINGS.verify_address)
         .show(comm, &addr_hex))
 }
@@ -0,0 +1,76 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use super::FERRIS;
+use crate::address_book::Contact;
+use crate::strings::STRINGS;
+use crate::AppSW;
+
+use ledger_device_sdk::io::Comm;
+use ledger_device_sdk::nbgl::{Field, NbglReview};
+
+use alloc::format;
+
+/// Displays a new address book entry and returns true if user approved it.
+///
+/// # Arguments
+///
+/// * `comm` - Reference to the communication object
+/// * `contact` - Address and label sent by the host
+pub fn ui_display_contact(comm: &mut Comm, contact: &Contact) -> Result<bool, AppSW> {
+    show_contact(comm, contact, STRINGS.review_contact, STRINGS.save_contact)
+}
+
+/// Displays an address book entry to be removed and returns true if user approved it.
+///
+/// # Arguments
+///
+/// * `comm` - Reference to the communication object
+/// * `contact` - Saved address and label
+pub fn ui_display_contact_removal(comm: &mut Comm, contact: &Contact) -> Result<bool, AppSW> {
+    show_contact(
+        comm,
+        contact,
+        STRINGS.review_contact_removal,
+        STRINGS.remove_contact,
+    )
+}
+
+fn show_contact(
+    comm: &mut Comm,
+    contact: &Contact,
+    title: &str,
+    finish_title: &str,
+) -> Result<bool, AppSW> {
+    let address_str = format!("0x{}", hex::encode(contact.address()).to_uppercase());
+
+    let my_fields = [
+        Field {
+            name: STRINGS.label,
+            value: contact.label(),
+        },
+        Field {
+            name: STRINGS.address,
+            value: address_str.as_str(),
+        },
+    ];
+
+    let review: NbglReview = NbglReview::new()
+        .titles(title, "", finish_title)
+        .glyph(&FERRIS);
+
+    Ok(review.show(comm, &my_fields))
+}
@@ -15,23 +15,16 @@
  *  limitations under the License.
  *****************************************************************************/
 
-use ledger_device_sdk::include_gif;
 use ledger_device_sdk::io::Comm;
 
-use crate::settings::Settings;
-use ledger_device_sdk::nbgl::{NbglGlyph, NbglHomeAndSettings};
+use super::HOME_GLYPH;
+use crate::address_book::AddressBook;
+use crate::replay_guard::ReplayGuard;
+use crate::settings::{Settings, SETTING_ADDRESS_BOOK, SETTING_REPLAY_GUARD};
+use crate::strings::STRINGS;
+use ledger_device_sdk::nbgl::NbglHomeAndSettings;
 
 pub fn ui_menu_main(_: &mut Comm) -> NbglHomeAndSettings {
-    // Load glyph from 64x64 4bpp gif file with include_gif macro. Creates an NBGL compatible glyph.
-    #[cfg(target_os = "apex_p")]
-    const FERRIS: NbglGlyph = NbglGlyph::from_include(include_gif!("glyphs/crab_48x48.png", NBGL));
-    #[cfg(any(target_os = "stax", target_os = "flex"))]
-    const FERRIS: NbglGlyph = NbglGlyph::from_include(include_gif!("glyphs/crab_64x64.gif", NBGL));
-    #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
-    const FERRIS: NbglGlyph =
-        NbglGlyph::from_include(include_gif!("glyphs/home_nano_nbgl.png", NBGL));
-
-    let settings_strings = [["Display Memo", "Allow display of transaction memo."]];
     let mut settings: Settings = Default::default();
 
     let title = if cfg!(feature = "variant_testnet") {
@@ -44,7 +37,24 @@ pub fn ui_menu_main(_: &mut Comm) -> NbglHomeAndSettings {
 
     // Display the home screen.
     NbglHomeAndSettings::new()
-        .glyph(&FERRIS)
-        .settings(settings.get_mut(), &settings_strings)
+        .glyph(&HOME_GLYPH)
+        .settings(settings.get_mut(), &STRINGS.settings)
         .infos(title, env!("CARGO_PKG_VERSION"), env!("CARGO_PKG_AUTHORS"))
 }
+
+/// Erases the data kept in NVM by the features disabled in the settings page: turning off
+/// "Replay protection" forgets the known nonces, turning off "Address book" erases the saved
+/// contacts.
+///
+/// The SDK toggles the switches of the settings page without notifying the app, so this is
+/// called when the app starts and before every APDU, before any of this data can be used.
+pub fn apply_settings() {
+    let settings: Settings = Default::default();
+    let guard = ReplayGuard;
+    if settings.get_element(SETTING_REPLAY_GUARD) == 0 && !guard.is_empty() {
+        guard.reset();
+    }
+    if settings.get_element(SETTING_ADDRESS_BOOK) == 0 {
+        AddressBook.clear();
+    }
+}
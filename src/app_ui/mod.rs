@@ -0,0 +1,43 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+//! User interface of the application: home screen, reviews and warnings.
+
+pub mod address;
+pub mod address_book;
+pub mod menu;
+pub mod sign;
+pub mod spending_policy;
+
+use ledger_device_sdk::include_gif;
+use ledger_device_sdk::nbgl::NbglGlyph;
+
+// Load glyph from file with include_gif macro. Creates an NBGL compatible glyph.
+/// Glyph of the application, shown on the reviews and warnings.
+#[cfg(target_os = "apex_p")]
+pub const FERRIS: NbglGlyph = NbglGlyph::from_include(include_gif!("glyphs/crab_48x48.png", NBGL));
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+pub const FERRIS: NbglGlyph = NbglGlyph::from_include(include_gif!("glyphs/crab_64x64.gif", NBGL));
+#[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+pub const FERRIS: NbglGlyph = NbglGlyph::from_include(include_gif!("icons/crab_14x14.gif", NBGL));
+
+/// Glyph of the home screen. Nano devices have a dedicated one, the others show [`FERRIS`].
+#[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+pub const HOME_GLYPH: NbglGlyph =
+    NbglGlyph::from_include(include_gif!("glyphs/home_nano_nbgl.png", NBGL));
+#[cfg(not(any(target_os = "nanosplus", target_os = "nanox")))]
+pub const HOME_GLYPH: NbglGlyph = FERRIS;
@@ -14,69 +14,433 @@
  *  See the License for the specific language governing permissions and
  *  limitations under the License.
  *****************************************************************************/
-use crate::handlers::sign_tx::Tx;
+use super::FERRIS;
+use crate::address_book::AddressBook;
+use crate::batch::Batch;
+use crate::risk::Risk;
+use crate::settings::{Settings, SETTING_DISPLAY_MEMO, SETTING_EXPERT_MODE};
+use crate::strings::{fill, STRINGS};
+use crate::token::{TokenInfo, MAX_TICKER_LEN};
+use crate::tx::Tx;
+use crate::utils::{format_amount, is_printable_ascii, Bip32Path};
 use crate::AppSW;
 
-use crate::settings::Settings;
-use ledger_device_sdk::include_gif;
 use ledger_device_sdk::io::Comm;
-use ledger_device_sdk::nbgl::{Field, NbglGlyph, NbglReview};
+use ledger_device_sdk::nbgl::{
+    Field, NbglChoice, NbglReview, NbglStatus, NbglStreamingReview, TransactionType,
+};
 
 use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Fields of the review of a transaction. Their values are the tags of the fields in the
+/// PARSE_TX response.
+#[derive(Clone, Copy)]
+pub enum TxField {
+    Amount = 0x01,
+    Token = 0x02,
+    Destination = 0x03,
+    Memo = 0x04,
+    Nonce = 0x05,
+    Path = 0x06,
+    Hash = 0x07,
+    From = 0x08,
+    Fees = 0x09,
+}
+
+impl TxField {
+    /// Name of the field on the review screens.
+    pub fn name(self) -> &'static str {
+        match self {
+            TxField::Amount => STRINGS.amount,
+            TxField::Token => STRINGS.token,
+            TxField::Destination => STRINGS.destination,
+            TxField::Memo => STRINGS.memo,
+            TxField::Nonce => STRINGS.nonce,
+            TxField::Path => STRINGS.path,
+            TxField::Hash => STRINGS.tx_hash,
+            TxField::From => STRINGS.from,
+            TxField::Fees => STRINGS.fees,
+        }
+    }
+}
+
+/// Formats the fields of the review of a transaction, in display order.
+///
+/// See [`for_each_tx_field`] for the fields and the errors returned.
+pub fn format_tx_fields(
+    tx: &Tx,
+    path: &Bip32Path,
+    from: &[u8; 20],
+    hash: &[u8; 32],
+    verified_name: Option<&str>,
+    token: Option<&TokenInfo>,
+) -> Result<Vec<(TxField, String)>, AppSW> {
+    let mut fields = Vec::with_capacity(8);
+    for_each_tx_field(
+        tx,
+        path,
+        from,
+        hash,
+        verified_name,
+        token,
+        |field, value| {
+            fields.push((field, String::from(value)));
+            true
+        },
+    )?;
+    Ok(fields)
+}
+
+/// Formats the fields of the review of a transaction one by one, in display order, and passes
+/// each of them to `push` as soon as it is formatted. Stops and returns false as soon as `push`
+/// returns false, returns true once all the fields are pushed.
+///
+/// This method returns [`AppSW::DisplayAmountFail`] if the coin ticker of a native coin transfer
+/// is longer than [`MAX_TICKER_LEN`], before pushing any field.
+///
+/// The fees are shown after the amount, unless there are none. The signing account is shown
+/// after the destination, see [`format_from`]. The memo is only
+/// shown if the "Display Memo" setting is enabled, see [`format_memo`]. When the "Expert mode"
+/// setting is enabled, the nonce, the signing path and the hash of the transaction are shown
+/// after the regular fields.
+///
+/// # Arguments
+///
+/// * `tx` - Transaction to be displayed for validation
+/// * `path` - BIP32 path of the signing key
+/// * `from` - Address of the signing key
+/// * `hash` - Keccak256 hash of the raw transaction, which is the message that will be signed
+/// * `verified_name` - Name of the destination from a trusted name descriptor, if any
+/// * `token` - Descriptor of the transferred token, if it is a verified token transfer
+/// * `push` - Called with each field and its value
+pub fn for_each_tx_field(
+    tx: &Tx,
+    path: &Bip32Path,
+    from: &[u8; 20],
+    hash: &[u8; 32],
+    verified_name: Option<&str>,
+    token: Option<&TokenInfo>,
+    mut push: impl FnMut(TxField, &str) -> bool,
+) -> Result<bool, AppSW> {
+    check_ticker(tx)?;
+    // Amounts of verified tokens are shown with their verified ticker and decimals, amounts of
+    // unverified tokens are shown as raw integers next to their contract address.
+    let value_str = match (token, tx.token) {
+        (Some(token), _) => format!(
+            "{} {}",
+            token.ticker(),
+            format_amount(tx.value, token.decimals())
+        ),
+        (None, Some(_)) => format!("{} {}", tx.value, STRINGS.unverified),
+        (None, None) => format!("{} {}", tx.coin, tx.value),
+    };
+    if !push(TxField::Amount, &value_str) {
+        return Ok(false);
+    }
+    // Fees are always paid in the native coin, and only shown when there are some
+    if tx.fee != 0 && !push(TxField::Fees, &format!("{} {}", tx.coin, tx.fee)) {
+        return Ok(false);
+    }
+    if let Some(address) = tx.token_address().ok().flatten() {
+        let token_str = format!("0x{}", hex::encode(address).to_uppercase());
+        if !push(TxField::Token, &token_str) {
+            return Ok(false);
+        }
+    }
+    let to_str = format_destination(&tx.to, verified_name);
+    if !push(TxField::Destination, &to_str) {
+        return Ok(false);
+    }
+    if !push(TxField::From, &format_from(path, from)) {
+        return Ok(false);
+    }
+
+    let settings: Settings = Default::default();
+    // If the "Display Memo" setting switch is disabled do not display the transaction memo
+    if settings.get_element(SETTING_DISPLAY_MEMO) != 0
+        && !push(TxField::Memo, &format_memo(tx.memo))
+    {
+        return Ok(false);
+    }
+    // Expert mode shows the data a power user may want to check against the host software
+    if settings.get_element(SETTING_EXPERT_MODE) != 0 {
+        let hash_str = format!("0x{}", hex::encode(hash).to_uppercase());
+        return Ok(push(TxField::Nonce, &format!("{}", tx.nonce))
+            && push(TxField::Path, &format!("{}", path))
+            && push(TxField::Hash, &hash_str));
+    }
+    Ok(true)
+}
+
+/// Returns [`AppSW::DisplayAmountFail`] if the coin ticker of a native coin transfer is longer
+/// than [`MAX_TICKER_LEN`].
+pub fn check_ticker(tx: &Tx) -> Result<(), AppSW> {
+    if tx.token.is_none() && tx.coin.len() > MAX_TICKER_LEN {
+        return Err(AppSW::DisplayAmountFail);
+    }
+    Ok(())
+}
+
+/// Formats the memo of a transaction. The memo is untrusted text: unless it only holds printable
+/// ASCII characters, it is shown in hex after a notice, so that control, bidirectional override
+/// or zero width characters cannot alter the review.
+fn format_memo(memo: &str) -> String {
+    if is_printable_ascii(memo) {
+        String::from(memo)
+    } else {
+        format!(
+            "{}\n0x{}",
+            STRINGS.non_ascii_memo,
+            hex::encode(memo).to_uppercase()
+        )
+    }
+}
+
+/// Formats the signing account: its index for a standard path, so that the user can tell which
+/// of their accounts is spending, and else its address.
+fn format_from(path: &Bip32Path, from: &[u8; 20]) -> String {
+    match path.account_index() {
+        Some(account) => format!("{} {}", STRINGS.account, account),
+        None => format!("0x{}", hex::encode(from).to_uppercase()),
+    }
+}
+
+/// Formats a destination address. The verified name, or else the label saved in the address
+/// book, is shown next to it.
+fn format_destination(to: &[u8; 20], verified_name: Option<&str>) -> String {
+    let to_hex = hex::encode(to).to_uppercase();
+    match (verified_name, AddressBook.find(to)) {
+        (Some(name), _) => format!("{} {}\n0x{}", name, STRINGS.verified, to_hex),
+        (None, Some(label)) => format!("{}\n0x{}", label, to_hex),
+        (None, None) => format!("0x{}", to_hex),
+    }
+}
 
 /// Displays a transaction and returns true if user approved it.
 ///
-/// This method can return [`AppSW::TxDisplayFail`] error if the coin name length is too long.
+/// The review is streamed: each field is pushed to the screen as soon as
+/// [`for_each_tx_field`] formats it, so that the number of fields and their total length are
+/// not bounded by the buffers of [`NbglReview`]. The errors of [`for_each_tx_field`] are
+/// returned before the review starts.
 ///
 /// # Arguments
 ///
 /// * `comm` - Reference to the communication object
 /// * `tx` - Transaction to be displayed for validation
-pub fn ui_display_tx(comm: &mut Comm, tx: &Tx) -> Result<bool, AppSW> {
-    let value_str = format!("{} {}", tx.coin, tx.value);
-    let to_str = format!("0x{}", hex::encode(tx.to).to_uppercase());
+/// * `path` - BIP32 path of the signing key
+/// * `from` - Address of the signing key
+/// * `hash` - Keccak256 hash of the raw transaction, which is the message that will be signed
+/// * `verified_name` - Name of the destination from a trusted name descriptor, if any
+/// * `token` - Descriptor of the transferred token, if it is a verified token transfer
+pub fn ui_display_tx(
+    comm: &mut Comm,
+    tx: &Tx,
+    path: &Bip32Path,
+    from: &[u8; 20],
+    hash: &[u8; 32],
+    verified_name: Option<&str>,
+    token: Option<&TokenInfo>,
+) -> Result<bool, AppSW> {
+    check_ticker(tx)?;
 
-    // Define transaction review fields
-    let my_fields = [
-        Field {
-            name: "Amount",
-            value: value_str.as_str(),
+    let mut review = NbglStreamingReview::new()
+        .glyph(&FERRIS)
+        .tx_type(TransactionType::Transaction);
+    if !review.start(comm, STRINGS.review_tx, None) {
+        return Ok(false);
+    }
+    // One field per page, pushed as it is formatted
+    let reviewed = for_each_tx_field(
+        tx,
+        path,
+        from,
+        hash,
+        verified_name,
+        token,
+        |field, value| {
+            review.continue_review(
+                comm,
+                &[Field {
+                    name: field.name(),
+                    value,
+                }],
+            )
         },
+    )?;
+    if !reviewed {
+        return Ok(false);
+    }
+    Ok(review.finish(comm, STRINGS.sign_tx))
+}
+
+/// Displays a transaction that could not be decoded and returns true if user approved it.
+///
+/// Only the Keccak256 hash of the raw transaction and the signing path can be shown. The review
+/// is preceded by the NBGL blind signing warning screen.
+///
+/// # Arguments
+///
+/// * `comm` - Reference to the communication object
+/// * `hash` - Keccak256 hash of the raw transaction, which is the message that will be signed
+/// * `path` - BIP32 path of the signing key
+pub fn ui_display_blind_tx(
+    comm: &mut Comm,
+    hash: &[u8; 32],
+    path: &Bip32Path,
+) -> Result<bool, AppSW> {
+    let hash_str = format!("0x{}", hex::encode(hash).to_uppercase());
+    let path_str = format!("{}", path);
+
+    let my_fields = [
         Field {
-            name: "Destination",
-            value: to_str.as_str(),
+            name: STRINGS.tx_hash,
+            value: hash_str.as_str(),
         },
         Field {
-            name: "Memo",
-            value: tx.memo,
+            name: STRINGS.path,
+            value: path_str.as_str(),
         },
     ];
 
-    // Create transaction review
+    let review: NbglReview = NbglReview::new()
+        .titles(STRINGS.review_tx, "", STRINGS.sign_blind_tx)
+        .glyph(&FERRIS)
+        .blind();
 
-    // Load glyph from file with include_gif macro. Creates an NBGL compatible glyph.
-    #[cfg(target_os = "apex_p")]
-    const FERRIS: NbglGlyph = NbglGlyph::from_include(include_gif!("glyphs/crab_48x48.png", NBGL));
-    #[cfg(any(target_os = "stax", target_os = "flex"))]
-    const FERRIS: NbglGlyph = NbglGlyph::from_include(include_gif!("glyphs/crab_64x64.gif", NBGL));
-    #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
-    const FERRIS: NbglGlyph = NbglGlyph::from_include(include_gif!("icons/crab_14x14.gif", NBGL));
+    Ok(review.show(comm, &my_fields))
+}
 
-    // Create NBGL review. Maximum number of fields and string buffer length can be customised
-    // with constant generic parameters of NbglReview. Default values are 32 and 1024 respectively.
-    let review: NbglReview = NbglReview::new()
-        .titles(
-            "Review transaction\nto send CRAB",
-            "",
-            "Sign transaction\nto send CRAB",
-        )
-        .glyph(&FERRIS);
+/// Displays a summary of a batch of transactions and returns true if user approved it.
+///
+/// The number of transactions, then the total value of each coin followed by its total fees
+/// unless there are none, and the destination of each transaction are shown. The memo of each
+/// transaction follows its destination if the "Display Memo" setting is enabled, see
+/// [`format_memo`]. As for a single transaction, the fields are streamed one per page, so that
+/// the length of the review is not bounded by the buffers of [`NbglReview`].
+///
+/// # Arguments
+///
+/// * `comm` - Reference to the communication object
+/// * `batch` - Batch of transactions, which were checked when added to it
+pub fn ui_display_batch(comm: &mut Comm, batch: &Batch) -> bool {
+    let mut review = NbglStreamingReview::new()
+        .glyph(&FERRIS)
+        .tx_type(TransactionType::Transaction);
+    if !review.start(comm, STRINGS.review_batch, None) {
+        return false;
+    }
+    // One field per page, pushed as it is formatted
+    let mut push = |name: &str, value: &str| review.continue_review(comm, &[Field { name, value }]);
 
-    // If first setting switch is disabled do not display the transaction memo
+    if !push(STRINGS.transactions, &format!("{}", batch.count())) {
+        return false;
+    }
+    for total in batch.totals() {
+        if !push(
+            STRINGS.total,
+            &format!("{} {}", total.coin(), total.value()),
+        ) {
+            return false;
+        }
+        if total.fees() != 0 && !push(STRINGS.fees, &format!("{} {}", total.coin(), total.fees())) {
+            return false;
+        }
+    }
     let settings: Settings = Default::default();
-    if settings.get_element(0) == 0 {
-        Ok(review.show(comm, &my_fields[0..2]))
-    } else {
-        Ok(review.show(comm, &my_fields))
+    let display_memo = settings.get_element(SETTING_DISPLAY_MEMO) != 0;
+    for (index, tx) in batch.txs().iter().enumerate() {
+        let name = format!("{} {}", STRINGS.destination, index + 1);
+        if !push(&name, &format_destination(tx.to(), None)) {
+            return false;
+        }
+        let name = format!("{} {}", STRINGS.memo, index + 1);
+        if display_memo && !push(&name, &format_memo(tx.memo())) {
+            return false;
+        }
     }
+    review.finish(comm, STRINGS.sign_batch)
+}
+
+/// Tells the user that a transaction was refused because it could not be decoded and blind
+/// signing is disabled in the settings.
+///
+/// # Arguments
+///
+/// * `comm` - Reference to the communication object
+pub fn ui_display_blind_signing_disabled(comm: &mut Comm) {
+    NbglStatus::new()
+        .text(STRINGS.blind_signing_disabled)
+        .show(comm, false);
+}
+
+/// Warns the user that the transaction nonce is not greater than the last one signed for this
+/// account, which may be the sign of a replayed transaction. Returns true if the user chose to
+/// continue to the review.
+///
+/// # Arguments
+///
+/// * `comm` - Reference to the communication object
+/// * `nonce` - Nonce of the transaction to be reviewed
+/// * `last_nonce` - Highest nonce already signed for this account
+pub fn ui_display_replay_warning(comm: &mut Comm, nonce: u64, last_nonce: u64) -> bool {
+    let sub_message = fill(STRINGS.replay_text, &[&nonce, &last_nonce]);
+    NbglChoice::new().glyph(&FERRIS).show(
+        comm,
+        STRINGS.replay,
+        sub_message.as_str(),
+        STRINGS.continue_anyway,
+        STRINGS.reject_tx,
+    )
+}
+
+/// Warns the user that the transaction is over the spending limits. Returns true if the user
+/// chose to continue to the review.
+///
+/// # Arguments
+///
+/// * `comm` - Reference to the communication object
+pub fn ui_display_spending_limit_warning(comm: &mut Comm) -> bool {
+    NbglChoice::new().glyph(&FERRIS).show(
+        comm,
+        STRINGS.spending_limit,
+        STRINGS.spending_limit_text,
+        STRINGS.continue_anyway,
+        STRINGS.reject_tx,
+    )
+}
+
+/// Tells the user that a transaction was refused because it is over the spending limits.
+///
+/// # Arguments
+///
+/// * `comm` - Reference to the communication object
+pub fn ui_display_spending_limit_refused(comm: &mut Comm) {
+    NbglStatus::new()
+        .text(STRINGS.spending_limit_refused)
+        .show(comm, false);
+}
+
+/// Warns the user about a risk of the transaction, flagged by [`crate::risk::assess`]. Returns
+/// true if the user chose to continue to the review.
+///
+/// # Arguments
+///
+/// * `comm` - Reference to the communication object
+/// * `risk` - Risk to warn about
+pub fn ui_display_risk_warning(comm: &mut Comm, risk: Risk) -> bool {
+    let (message, sub_message) = match risk {
+        Risk::SelfTransfer => (STRINGS.self_transfer, STRINGS.self_transfer_text),
+        Risk::ZeroValue => (STRINGS.zero_value, STRINGS.zero_value_text),
+        Risk::UnverifiedToken => (STRINGS.unverified_token, STRINGS.unverified_token_text),
+        Risk::HighValue => (STRINGS.high_value, STRINGS.high_value_text),
+    };
+    NbglChoice::new().glyph(&FERRIS).show(
+        comm,
+        message,
+        sub_message,
+        STRINGS.continue_anyway,
+        STRINGS.reject_tx,
+    )
 }
@@ -0,0 +1,80 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use super::FERRIS;
+use crate::spending_policy::{LimitAction, Policy, CUMULATIVE_WINDOW};
+use crate::strings::{fill, STRINGS};
+use crate::AppSW;
+
+use ledger_device_sdk::io::Comm;
+use ledger_device_sdk::nbgl::{Field, NbglReview};
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+
+/// Displays a new spending policy and returns true if user approved it.
+///
+/// # Arguments
+///
+/// * `comm` - Reference to the communication object
+/// * `policy` - Spending policy sent by the host
+pub fn ui_display_policy(comm: &mut Comm, policy: &Policy) -> Result<bool, AppSW> {
+    let max_per_tx_str = format!("CRAB {}", policy.max_per_tx);
+    let cumulative_str = if policy.cumulative_limit == 0 {
+        String::from(STRINGS.no_limit)
+    } else {
+        format!(
+            "CRAB {}\n{}",
+            policy.cumulative_limit,
+            fill(STRINGS.cumulative_window, &[&CUMULATIVE_WINDOW])
+        )
+    };
+    let action_str = match policy.action {
+        LimitAction::Warn => STRINGS.warn,
+        LimitAction::Refuse => STRINGS.refuse,
+    };
+
+    let high_value_str = format!("CRAB {}", policy.high_value_threshold);
+
+    let mut my_fields = vec![
+        Field {
+            name: STRINGS.max_per_tx,
+            value: max_per_tx_str.as_str(),
+        },
+        Field {
+            name: STRINGS.cumulative_limit,
+            value: cumulative_str.as_str(),
+        },
+        Field {
+            name: STRINGS.over_limit,
+            value: action_str,
+        },
+    ];
+    // The threshold is optional, and only shown when set
+    if policy.high_value_threshold != u64::MAX {
+        my_fields.push(Field {
+            name: STRINGS.high_value_above,
+            value: high_value_str.as_str(),
+        });
+    }
+
+    let review: NbglReview = NbglReview::new()
+        .titles(STRINGS.review_policy, "", STRINGS.set_policy)
+        .glyph(&FERRIS);
+
+    Ok(review.show(comm, &my_fields))
+}
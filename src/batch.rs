@@ -0,0 +1,221 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+//! Transaction batches
+//!
+//! A batch is a list of transactions signed with the same key behind a single review, which
+//! shows the number of transactions, the total value and fees of each coin, and the destination
+//! and memo of each transaction. Once the user approves it, the signatures are read one by one.
+//!
+//! A batch holds up to [`MAX_BATCH_SIZE`] native coin transfers with distinct nonces, whose
+//! total value, whatever their coin, is at most [`MAX_BATCH_TOTAL`]. Each transaction is decoded
+//! and assessed once, when it is added: the review only shows the data kept in the batch.
+
+use crate::risk::{self, RiskFlags};
+use crate::token::MAX_TICKER_LEN;
+use crate::tx::Tx;
+use crate::utils::Bip32Path;
+use crate::AppSW;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Maximum number of transactions in a batch.
+pub const MAX_BATCH_SIZE: usize = 8;
+/// Maximum total value of a batch, 1,000,000 CRAB (9 decimals). The spending limits, when
+/// enforced, apply on top of it.
+pub const MAX_BATCH_TOTAL: u64 = 1_000_000_000_000_000;
+
+/// Transaction of a batch.
+pub struct BatchTx {
+    /// Raw transaction, which is signed once the batch is approved.
+    raw: Vec<u8>,
+    nonce: u64,
+    to: [u8; 20],
+    memo: String,
+}
+
+impl BatchTx {
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    pub fn to(&self) -> &[u8; 20] {
+        &self.to
+    }
+
+    pub fn memo(&self) -> &str {
+        &self.memo
+    }
+}
+
+/// Total value and fees of the transactions of a batch that transfer the same coin.
+pub struct CoinTotal {
+    coin: String,
+    value: u64,
+    fees: u64,
+}
+
+impl CoinTotal {
+    pub fn coin(&self) -> &str {
+        &self.coin
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    pub fn fees(&self) -> u64 {
+        self.fees
+    }
+}
+
+/// Transactions of the batch being received, or approved.
+#[derive(Default)]
+pub struct Batch {
+    /// Derivation path of the signing key, `None` until it is received.
+    path: Option<Bip32Path>,
+    /// Transactions, in reception order.
+    txs: Vec<BatchTx>,
+    /// Totals of each coin, in the order of their first transaction.
+    totals: Vec<CoinTotal>,
+    /// Total value of the batch, whatever the coin.
+    total: u64,
+    /// Risks raised by any of the transactions.
+    risks: RiskFlags,
+    /// Whether the user approved the batch.
+    approved: bool,
+}
+
+impl Batch {
+    /// Starts a new batch, signed with the key at `path`.
+    pub fn start(&mut self, path: Bip32Path) {
+        *self = Batch {
+            path: Some(path),
+            ..Default::default()
+        };
+    }
+
+    /// Adds a transaction to the batch, which must be started and not approved yet, and assesses
+    /// its risks. `from` is the address of the signing key.
+    ///
+    /// Returns [`AppSW::BatchTooLarge`] if the batch is full, the errors of [`Tx::parse`],
+    /// [`AppSW::BatchTokenTransfer`] for a token transfer, [`AppSW::DisplayAmountFail`] if the
+    /// coin ticker is longer than [`MAX_TICKER_LEN`], [`AppSW::BatchDuplicateNonce`] if a
+    /// previous transaction has the same nonce and [`AppSW::BatchValueTooHigh`] if the total
+    /// value of the batch goes over [`MAX_BATCH_TOTAL`], or the total fees of its coin overflow.
+    pub fn add(&mut self, raw_tx: &[u8], from: &[u8; 20]) -> Result<(), AppSW> {
+        if self.path.is_none() || self.approved {
+            return Err(AppSW::BadState);
+        }
+        if self.txs.len() == MAX_BATCH_SIZE {
+            return Err(AppSW::BatchTooLarge);
+        }
+        let tx = Tx::parse(raw_tx)?;
+        if tx.token.is_some() {
+            return Err(AppSW::BatchTokenTransfer);
+        }
+        if tx.coin.len() > MAX_TICKER_LEN {
+            return Err(AppSW::DisplayAmountFail);
+        }
+        if self.txs.iter().any(|known| known.nonce == tx.nonce) {
+            return Err(AppSW::BatchDuplicateNonce);
+        }
+        let total = self
+            .total
+            .checked_add(tx.value)
+            .filter(|total| *total <= MAX_BATCH_TOTAL)
+            .ok_or(AppSW::BatchValueTooHigh)?;
+        // The value of a coin is at most the total value, only its fees may overflow
+        let coin_total = self.totals.iter().position(|known| known.coin == tx.coin);
+        let fees = coin_total
+            .map_or(0, |index| self.totals[index].fees)
+            .checked_add(tx.fee)
+            .ok_or(AppSW::BatchValueTooHigh)?;
+
+        match coin_total {
+            Some(index) => {
+                self.totals[index].value += tx.value;
+                self.totals[index].fees = fees;
+            }
+            None => self.totals.push(CoinTotal {
+                coin: String::from(tx.coin),
+                value: tx.value,
+                fees,
+            }),
+        }
+        self.total = total;
+        risk::assess(&tx, from, None)
+            .iter()
+            .for_each(|risk| self.risks.insert(risk));
+        self.txs.push(BatchTx {
+            raw: raw_tx.to_vec(),
+            nonce: tx.nonce,
+            to: tx.to,
+            memo: String::from(tx.memo),
+        });
+        Ok(())
+    }
+
+    /// Marks the batch as approved by the user, so that its signatures can be read.
+    pub fn approve(&mut self) {
+        self.approved = true;
+    }
+
+    /// Derivation path of the signing key, if the batch was started.
+    pub fn path(&self) -> Option<&Bip32Path> {
+        self.path.as_ref()
+    }
+
+    /// Number of transactions in the batch.
+    pub fn count(&self) -> usize {
+        self.txs.len()
+    }
+
+    /// Transactions, in reception order.
+    pub fn txs(&self) -> &[BatchTx] {
+        &self.txs
+    }
+
+    /// Totals of each coin, in the order of their first transaction.
+    pub fn totals(&self) -> &[CoinTotal] {
+        &self.totals
+    }
+
+    /// Total value of the batch, whatever the coin.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Risks raised by any of the transactions.
+    pub fn risks(&self) -> RiskFlags {
+        self.risks
+    }
+
+    /// Returns the raw transaction at `index` of an approved batch.
+    ///
+    /// Returns [`AppSW::BadState`] if the batch was not approved and [`AppSW::WrongP1P2`] if
+    /// there is no transaction at `index`.
+    pub fn approved_tx(&self, index: usize) -> Result<&[u8], AppSW> {
+        if !self.approved {
+            return Err(AppSW::BadState);
+        }
+        self.txs
+            .get(index)
+            .map(|tx| tx.raw.as_slice())
+            .ok_or(AppSW::WrongP1P2)
+    }
+}
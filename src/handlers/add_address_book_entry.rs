@@ -0,0 +1,51 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use crate::address_book::{AddressBook, Contact};
+use crate::app_ui::address_book::ui_display_contact;
+use crate::handlers::sign_tx::TxContext;
+use crate::AppSW;
+use ledger_device_sdk::io::{Command, CommandResponse};
+use ledger_device_sdk::log;
+
+/// Handler for ADD_ADDRESS_BOOK_ENTRY APDU command.
+///
+/// Parses the address and label sent by the host, displays them for confirmation and, if the
+/// user approves, saves them in the address book.
+///
+/// Returns [`AppSW::AddressBookDisabled`] if the "Address book" setting is disabled,
+/// [`AppSW::AddressBookDuplicate`] if the label is already saved and [`AppSW::AddressBookFull`]
+/// if there is no free entry, without displaying anything.
+pub fn handler_add_address_book_entry<'a>(
+    command: Command<'a>,
+    ctx: &mut TxContext,
+) -> Result<CommandResponse<'a>, AppSW> {
+    log::debug!("=> handler_add_address_book_entry");
+    ctx.review_finished = false;
+    let contact: Contact = command.get_data().try_into()?;
+    // Every check that may fail is made before the review
+    AddressBook.slot(&contact)?;
+
+    let comm = command.into_comm();
+    ctx.review_finished = true;
+    if !ui_display_contact(comm, &contact)? {
+        return Err(AppSW::Deny);
+    }
+
+    AddressBook.add(&contact)?;
+    log::debug!("Address book entry saved");
+    Ok(comm.begin_response())
+}
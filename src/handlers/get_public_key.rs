@@ -66,7 +66,7 @@ pub fn handler_get_public_key(
 
     // Return chaincode
     const CHAINCODE_LEN: u8 = 32;
-    let code = cc.unwrap();
+    let code = cc.ok_or(AppSW::KeyDeriveFail)?;
     response.append(&[CHAINCODE_LEN])?;
     response.append(&code.value)?;
 
@@ -0,0 +1,79 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use crate::app_ui::sign::format_tx_fields;
+use crate::handlers::sign_tx::{compute_tx_hash, TxContext};
+use crate::tx::Tx;
+use crate::utils::get_address_from_path;
+use crate::AppSW;
+use ledger_device_sdk::io::{Command, CommandResponse};
+use ledger_device_sdk::log;
+
+/// Handler for the PARSE_TX APDU command.
+///
+/// Receives the same chunks as SIGN_TX, then parses the transaction and formats its review
+/// fields as [`handler_sign_tx`](crate::handlers::sign_tx::handler_sign_tx) would, without
+/// displaying nor signing anything. It lets hosts check what the user will be shown.
+///
+/// The trusted name provided for the next transaction, if any, is used but not consumed. A
+/// transaction that cannot be parsed is refused with [`AppSW::TxParsingFail`], whatever the
+/// "Blind signing" setting.
+///
+/// # Response
+///
+/// The fields in display order, each encoded as:
+/// - 1 byte: Tag, see [`TxField`](crate::app_ui::sign::TxField)
+/// - 1 byte: Length of the value
+/// - Value: Formatted UTF-8 string
+///
+/// Fields that do not fit in the response are refused with [`AppSW::WrongResponseLength`].
+pub fn handler_parse_tx<'a>(
+    command: Command<'a>,
+    chunk: u8,
+    more: bool,
+    ctx: &mut TxContext,
+) -> Result<CommandResponse<'a>, AppSW> {
+    log::debug!("=> handler_parse_tx");
+    if !ctx.receive_chunk(command.get_data(), chunk, more, true)? {
+        return Ok(command.into_response());
+    }
+
+    let tx = Tx::parse(ctx.raw_tx())?;
+    let verified_name = ctx
+        .trusted_name
+        .as_ref()
+        .filter(|trusted_name| *trusted_name.address() == tx.to)
+        .map(|trusted_name| trusted_name.name());
+    let token = tx
+        .token_address()?
+        .and_then(|address| ctx.tokens.find(&address));
+    let fields = format_tx_fields(
+        &tx,
+        ctx.path(),
+        &get_address_from_path(ctx.path())?,
+        &compute_tx_hash(ctx.raw_tx()),
+        verified_name,
+        token,
+    )?;
+
+    let mut response = command.into_response();
+    for (field, value) in fields {
+        let len = u8::try_from(value.len()).map_err(|_| AppSW::WrongResponseLength)?;
+        response.append(&[field as u8, len])?;
+        response.append(value.as_bytes())?;
+    }
+    Ok(response)
+}
@@ -0,0 +1,36 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use crate::handlers::sign_tx::TxContext;
+use crate::token::TokenInfo;
+use crate::AppSW;
+use ledger_device_sdk::io::{Command, CommandResponse};
+use ledger_device_sdk::log;
+
+/// Handler for PROVIDE_TOKEN_INFO APDU command.
+///
+/// Verifies the signature of a token descriptor and adds it to the token registry, so that
+/// transfers of this token are reviewed with its verified ticker and decimals.
+pub fn handler_provide_token_info<'a>(
+    command: Command<'a>,
+    ctx: &mut TxContext,
+) -> Result<CommandResponse<'a>, AppSW> {
+    log::debug!("=> handler_provide_token_info");
+    let token: TokenInfo = command.get_data().try_into()?;
+    log::debug!("Token info verified");
+    ctx.tokens.add(token);
+    Ok(command.into_response())
+}
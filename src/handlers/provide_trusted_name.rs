@@ -0,0 +1,39 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use crate::handlers::sign_tx::TxContext;
+use crate::trusted_name::TrustedName;
+use crate::AppSW;
+use ledger_device_sdk::io::{Command, CommandResponse};
+use ledger_device_sdk::log;
+
+/// Handler for PROVIDE_TRUSTED_NAME APDU command.
+///
+/// Verifies the signature of a trusted name descriptor and caches it in the transaction context.
+/// The name is displayed, marked as verified, if the next transaction is sent to the same address.
+/// The cached descriptor is dropped once that transaction review is finished.
+pub fn handler_provide_trusted_name<'a>(
+    command: Command<'a>,
+    ctx: &mut TxContext,
+) -> Result<CommandResponse<'a>, AppSW> {
+    log::debug!("=> handler_provide_trusted_name");
+    // Drop any previous descriptor, even if the new one is invalid
+    ctx.trusted_name = None;
+    let trusted_name: TrustedName = command.get_data().try_into()?;
+    log::debug!("Trusted name verified");
+    ctx.trusted_name = Some(trusted_name);
+    Ok(command.into_response())
+}
@@ -0,0 +1,59 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use crate::address_book::AddressBook;
+use crate::app_ui::address_book::ui_display_contact_removal;
+use crate::handlers::sign_tx::TxContext;
+use crate::AppSW;
+use ledger_device_sdk::io::{Command, CommandResponse};
+use ledger_device_sdk::log;
+
+/// Handler for REMOVE_ADDRESS_BOOK_ENTRY APDU command.
+///
+/// Looks up the contact saved for the 20-byte address sent by the host, displays it for
+/// confirmation and, if the user approves, removes it from the address book.
+///
+/// Returns [`AppSW::AddressBookDisabled`] if the "Address book" setting is disabled, and
+/// [`AppSW::AddressBookEntryNotFound`] if no contact is saved for the address, without
+/// displaying anything.
+pub fn handler_remove_address_book_entry<'a>(
+    command: Command<'a>,
+    ctx: &mut TxContext,
+) -> Result<CommandResponse<'a>, AppSW> {
+    log::debug!("=> handler_remove_address_book_entry");
+    ctx.review_finished = false;
+    let address: [u8; 20] = command
+        .get_data()
+        .try_into()
+        .map_err(|_| AppSW::WrongApduLength)?;
+
+    if !AddressBook.enabled() {
+        return Err(AppSW::AddressBookDisabled);
+    }
+    let contact = *AddressBook
+        .contact(&address)
+        .ok_or(AppSW::AddressBookEntryNotFound)?;
+
+    let comm = command.into_comm();
+    ctx.review_finished = true;
+    if !ui_display_contact_removal(comm, &contact)? {
+        return Err(AppSW::Deny);
+    }
+
+    AddressBook.remove(&address)?;
+    log::debug!("Address book entry removed");
+    Ok(comm.begin_response())
+}
@@ -0,0 +1,41 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use crate::app_ui::spending_policy::ui_display_policy;
+use crate::spending_policy::{Policy, SpendingPolicy};
+use crate::AppSW;
+use ledger_device_sdk::io::{Command, CommandResponse};
+use ledger_device_sdk::log;
+
+/// Handler for SET_SPENDING_POLICY APDU command.
+///
+/// Parses the spending limits sent by the host, displays them for confirmation and, if the user
+/// approves, stores them in NVM. The cumulative window is emptied.
+///
+/// The limits are only enforced while the "Spending limits" setting is enabled.
+pub fn handler_set_spending_policy(command: Command<'_>) -> Result<CommandResponse<'_>, AppSW> {
+    log::debug!("=> handler_set_spending_policy");
+    let policy: Policy = command.get_data().try_into()?;
+
+    let comm = command.into_comm();
+    if !ui_display_policy(comm, &policy)? {
+        return Err(AppSW::Deny);
+    }
+
+    SpendingPolicy.set(policy);
+    log::debug!("Spending policy updated");
+    Ok(comm.begin_response())
+}
@@ -14,40 +14,56 @@
  *  See the License for the specific language governing permissions and
  *  limitations under the License.
  *****************************************************************************/
-use crate::app_ui::sign::ui_display_tx;
-use crate::utils::Bip32Path;
+use crate::app_ui::sign::{
+    check_ticker, ui_display_blind_signing_disabled, ui_display_blind_tx,
+    ui_display_replay_warning, ui_display_risk_warning, ui_display_spending_limit_refused,
+    ui_display_spending_limit_warning, ui_display_tx,
+};
+use crate::batch::Batch;
+use crate::replay_guard::ReplayGuard;
+use crate::risk::{self, RiskFlags};
+use crate::settings::{Settings, SETTING_BLIND_SIGNING, SETTING_REPLAY_GUARD};
+use crate::spending_policy::{LimitCheck, SpendingPolicy};
+use crate::token::TokenRegistry;
+use crate::trusted_name::TrustedName;
+use crate::tx::Tx;
+use crate::utils::{get_address_from_path, Bip32Path};
 use crate::AppSW;
 use alloc::vec::Vec;
 use ledger_device_sdk::ecc::{Secp256k1, SeedDerive};
 use ledger_device_sdk::hash::{sha3::Keccak256, HashInit};
-use ledger_device_sdk::io::{Command, CommandResponse};
+use ledger_device_sdk::io::{Comm, Command, CommandResponse};
 use ledger_device_sdk::log;
 use ledger_device_sdk::nbgl::NbglHomeAndSettings;
 
-use serde::Deserialize;
-use serde_json_core::from_slice;
-
 const MAX_TRANSACTION_LEN: usize = 510;
 
 use ledger_device_sdk::libcall::swap::CreateTxParams;
 
-#[derive(Deserialize)]
-pub struct Tx<'a> {
-    #[allow(dead_code)]
-    nonce: u64,
-    pub coin: &'a str,
-    pub value: u64,
-    #[serde(with = "hex::serde")] // Allows JSON deserialization from hex string
-    pub to: [u8; 20],
-    pub memo: &'a str,
-}
-
 /// Transaction context holding state between APDU chunks.
 pub struct TxContext<'a> {
     raw_tx: Vec<u8>,
     path: Bip32Path,
-    review_finished: bool,
+    /// Number of the next expected data chunk, 0 if the derivation path was not received.
+    next_chunk: u8,
+    /// Whether the chunks are sent with PARSE_TX rather than SIGN_TX.
+    dry_run: bool,
+    /// Whether the transaction hash is returned with the signature.
+    return_hash: bool,
+    /// Whether the risk flags are returned with the signature.
+    return_risk: bool,
+    /// Risk flags of the transaction being signed.
+    risks: RiskFlags,
+    /// Whether the review of the last transaction, batch or address book entry has started.
+    /// Whatever its outcome, the home screen is then shown again.
+    pub review_finished: bool,
     pub home: NbglHomeAndSettings,
+    /// Trusted name descriptor provided for the next transaction, if any.
+    pub trusted_name: Option<TrustedName>,
+    /// Token descriptors provided during the session.
+    pub tokens: TokenRegistry,
+    /// Batch of transactions being received, or approved.
+    pub batch: Batch,
     /// Swap parameters if running in swap mode.
     /// Used to validate the transaction against the Exchange's request.
     pub swap_params: Option<&'a CreateTxParams>,
@@ -60,8 +76,16 @@ impl<'a> TxContext<'a> {
         TxContext {
             raw_tx: Vec::new(),
             path: Default::default(),
+            next_chunk: 0,
+            dry_run: false,
+            return_hash: false,
+            return_risk: false,
+            risks: Default::default(),
             review_finished: false,
             home: Default::default(),
+            trusted_name: None,
+            tokens: Default::default(),
+            batch: Default::default(),
             swap_params: None,
         }
     }
@@ -70,8 +94,16 @@ impl<'a> TxContext<'a> {
         TxContext {
             raw_tx: Vec::new(),
             path: Default::default(),
+            next_chunk: 0,
+            dry_run: false,
+            return_hash: false,
+            return_risk: false,
+            risks: Default::default(),
             review_finished: false,
             home: Default::default(),
+            trusted_name: None,
+            tokens: Default::default(),
+            batch: Default::default(),
             swap_params: Some(params),
         }
     }
@@ -85,112 +117,321 @@ impl<'a> TxContext<'a> {
     fn reset(&mut self) {
         self.raw_tx.clear();
         self.path = Default::default();
+        self.next_chunk = 0;
+        self.dry_run = false;
+        self.return_hash = false;
+        self.return_risk = false;
+        self.risks = Default::default();
         self.review_finished = false;
     }
+
+    /// Receives a chunk of the payload of SIGN_TX or PARSE_TX: the derivation path in chunk 0,
+    /// then the raw transaction. Returns true once the last chunk has been received.
+    ///
+    /// Chunks received out of sequence, or following the derivation path sent with the other
+    /// instruction, are refused with [`AppSW::BadState`].
+    pub fn receive_chunk(
+        &mut self,
+        data: &[u8],
+        chunk: u8,
+        more: bool,
+        dry_run: bool,
+    ) -> Result<bool, AppSW> {
+        // First chunk, try to parse the path
+        if chunk == 0 {
+            log::debug!("Chunk 0: Path parsing");
+            // Reset transaction context
+            self.reset();
+            // This will propagate the error if the path is invalid
+            self.path = data.try_into()?;
+            self.next_chunk = 1;
+            self.dry_run = dry_run;
+            return Ok(false);
+        }
+        // Next chunks must follow the derivation path, in order
+        if chunk != self.next_chunk || dry_run != self.dry_run {
+            return Err(AppSW::BadState);
+        }
+        if self.raw_tx.len() + data.len() > MAX_TRANSACTION_LEN {
+            return Err(AppSW::TxWrongLength);
+        }
+
+        // Append data to raw_tx
+        self.raw_tx.extend(data);
+
+        if more {
+            self.next_chunk += 1;
+        } else {
+            // A new transaction must start with its derivation path
+            self.next_chunk = 0;
+        }
+        Ok(!more)
+    }
+
+    /// Derivation path received in the first chunk.
+    pub fn path(&self) -> &Bip32Path {
+        &self.path
+    }
+
+    /// Raw transaction received in the next chunks.
+    pub fn raw_tx(&self) -> &[u8] {
+        &self.raw_tx
+    }
 }
 
 /// Handler for the Sign Transaction APDU.
 ///
-/// Receives transaction chunks, parses them, and signs the transaction.
+/// Receives transaction chunks, parses them, and signs the transaction. The first chunk holds
+/// the derivation path, chunks received out of sequence are refused with [`AppSW::BadState`].
 ///
 /// # Swap Mode
 ///
 /// If `ctx.swap_params` is present:
 /// 1. Validates the parsed transaction against the swap parameters (amount, destination).
 /// 2. If valid, bypasses the UI and signs immediately.
-/// 3. If invalid, returns an error.
+/// 3. If invalid, or if the transaction cannot be parsed, returns [`AppSW::SwapFail`] with the
+///    swap error in the response data.
+///
+/// # Blind Signing
+///
+/// In normal mode, a transaction that cannot be parsed is only signed if the "Blind signing"
+/// setting is enabled, after a review of its hash and signing path. Otherwise the user is told
+/// why it was refused and [`AppSW::TxParsingFail`] is returned.
+///
+/// # Replay Protection
+///
+/// If the "Replay protection" setting is enabled, the nonce of every signed transaction is
+/// recorded per account. In normal mode, a transaction whose nonce is not greater than the last
+/// one signed for the same account is shown behind a "possible replay" warning.
+///
+/// # Spending Limits
+///
+/// If the "Spending limits" setting is enabled, the transaction value is checked against the
+/// [`SpendingPolicy`]. Depending on the policy, a transaction over a limit is shown behind a
+/// warning or refused with [`AppSW::SpendingLimitExceeded`]. In swap mode, it is always refused.
+///
+/// # Risk Assessment
+///
+/// A decoded transaction is assessed by [`risk::assess`] before its review, each flagged risk
+/// adds a warning page the user must accept.
+///
+/// # Response
+///
+/// The response holds the length of the DER signature, the signature and the parity of its Y
+/// coordinate. If `return_hash` was set with the derivation path, it is followed by the 32-byte
+/// Keccak256 hash of the raw transaction, which is the message that was signed. If
+/// `return_risk` was set with the derivation path, the response ends with one byte of
+/// [`RiskFlags`], 0 for a blindly signed transaction and in swap mode.
 pub fn handler_sign_tx<'a>(
     command: Command<'a>,
     chunk: u8,
     more: bool,
+    return_hash: bool,
+    return_risk: bool,
     ctx: &mut TxContext,
 ) -> Result<CommandResponse<'a>, AppSW> {
     log::debug!("=> handler_sign_tx");
-    // Try to get data from command
-    let data = command.get_data();
-    // First chunk, try to parse the path
-    if chunk == 0 {
-        log::debug!("Chunk 0: Path parsing");
-        // Reset transaction context
-        ctx.reset();
-        // This will propagate the error if the path is invalid
-        ctx.path = data.try_into()?;
-        Ok(command.into_response())
-    // Next chunks, append data to raw_tx and return or parse
-    // the transaction if it is the last chunk.
-    } else {
-        if ctx.raw_tx.len() + data.len() > MAX_TRANSACTION_LEN {
-            return Err(AppSW::TxWrongLength);
+    ctx.review_finished = false;
+    if !ctx.receive_chunk(command.get_data(), chunk, more, false)? {
+        if chunk == 0 {
+            ctx.return_hash = return_hash;
+            ctx.return_risk = return_risk;
         }
+        return Ok(command.into_response());
+    }
 
-        // Append data to raw_tx
-        ctx.raw_tx.extend(data);
+    // --8<-- [start:ui_bypass]
+    log::debug!("Last chunk received, parsing tx");
+    // Try to deserialize the transaction
+    let parsed = Tx::parse(&ctx.raw_tx);
+    // A trusted name is only valid for the transaction that follows it
+    let trusted_name = ctx.trusted_name.take();
+
+    // Check if in swap mode
+    if let Some(params) = ctx.swap_params {
+        ctx.review_finished = true;
+        // --8<-- [start:SwapError_usage]
+        let checked = match parsed {
+            Ok(tx) => {
+                log::debug!("Tx parsed successfully");
+                // Spending limits are expressed in the native coin, token transfers are not
+                // counted.
+                let native_value = if tx.token.is_none() { tx.value } else { 0 };
+                crate::swap::check_swap_params(params, &ctx.path, &tx, &ctx.tokens)
+                    .map(|_| (tx.nonce, native_value))
+            }
+            Err(_) => Err(crate::swap::tx_parsing_error()),
+        };
+        match checked {
+            Err(error) => {
+                // The swap validation failed and returned us the common format error defined by the SDK
+                // Use SDK method to append error code and message in standard format
+                let mut response = command.into_response();
+                error.append_to_response(&mut response)?;
+                Err(AppSW::SwapFail)
+            }
+            Ok((nonce, value)) => {
+                log::debug!("Swap validation success, bypassing UI");
+                let response = compute_signature_and_append(command.into_response(), ctx)?;
+                record_nonce(&ctx.path, nonce);
+                SpendingPolicy.record(value);
+                Ok(response)
+            }
+        }
+        // --8<-- [end:SwapError_usage]
+    } else {
+        log::debug!("Normal mode, showing UI");
+        // Display transaction. If user approves
+        // the transaction, sign it. Otherwise,
+        // return a "deny" status word.
+
+        let comm = command.into_comm();
+        // Nonce and value of the parsed transaction, to be recorded once signed
+        let mut nonce_and_value = None;
+
+        let approved = match parsed {
+            Ok(tx) => {
+                log::debug!("Tx parsed successfully");
+                // Spending limits are expressed in the native coin, token transfers are
+                // neither checked nor counted.
+                let native_value = if tx.token.is_none() { tx.value } else { 0 };
+                nonce_and_value = Some((tx.nonce, native_value));
+                // Every check that may fail is made before the first page is shown, so that an
+                // error never interrupts the warnings or the review.
+                check_ticker(&tx)?;
+                let verified_name = trusted_name
+                    .as_ref()
+                    .filter(|trusted_name| *trusted_name.address() == tx.to)
+                    .map(|trusted_name| trusted_name.name());
+                let token_address = tx.token_address()?;
+                let token = token_address.and_then(|address| ctx.tokens.find(&address));
+                // The signing account is shown in the review, and checked against the destination
+                let from = get_address_from_path(&ctx.path)?;
+                ctx.risks = risk::assess(&tx, &from, token);
 
-        // If we expect more chunks, return
-        if more {
-            ctx.review_finished = false;
-            Ok(command.into_response())
-        // Otherwise, try to parse the transaction
-        } else {
-            // --8<-- [start:ui_bypass]
-            log::debug!("Last chunk received, parsing tx");
-            // Try to deserialize the transaction
-            let (tx, _): (Tx, usize) = from_slice(&ctx.raw_tx).map_err(|_| AppSW::TxParsingFail)?;
-            log::debug!("Tx parsed successfully");
-
-            // Check if in swap mode
-            if let Some(params) = ctx.swap_params {
                 ctx.review_finished = true;
-                // --8<-- [start:SwapError_usage]
-                if let Err(error) = crate::swap::check_swap_params(params, &tx) {
-                    // The swap validation failed and returned us the common format error defined by the SDK
-                    // Use SDK method to append error code and message in standard format
-                    let mut response = command.into_response();
-                    error.append_to_response(&mut response)?;
-                    Err(AppSW::SwapFail)
-                } else {
-                    log::debug!("Swap validation success, bypassing UI");
-                    compute_signature_and_append(command.into_response(), ctx)
-                }
-                // --8<-- [end:SwapError_usage]
-            } else {
-                log::debug!("Normal mode, showing UI");
-                // Display transaction. If user approves
-                // the transaction, sign it. Otherwise,
-                // return a "deny" status word.
-
-                let comm = command.into_comm();
-
-                if ui_display_tx(comm, &tx)? {
-                    ctx.review_finished = true;
-                    compute_signature_and_append(comm.begin_response(), ctx)
-                } else {
-                    ctx.review_finished = true;
-                    Err(AppSW::Deny)
+                let within_limits = match SpendingPolicy.check(native_value) {
+                    LimitCheck::Allowed => true,
+                    LimitCheck::Warn => ui_display_spending_limit_warning(comm),
+                    LimitCheck::Refuse => {
+                        log::debug!("Tx over the spending limits, refusing");
+                        ui_display_spending_limit_refused(comm);
+                        return Err(AppSW::SpendingLimitExceeded);
+                    }
+                };
+                // Each page is only shown if the user accepted the previous ones
+                within_limits
+                    && ctx
+                        .risks
+                        .iter()
+                        .all(|risk| ui_display_risk_warning(comm, risk))
+                    && check_replay(comm, &ctx.path, tx.nonce)
+                    && ui_display_tx(
+                        comm,
+                        &tx,
+                        &ctx.path,
+                        &from,
+                        &compute_tx_hash(&ctx.raw_tx),
+                        verified_name,
+                        token,
+                    )?
+            }
+            // A memo over the length cap is refused, whatever the blind signing setting
+            Err(AppSW::MemoTooLong) => return Err(AppSW::MemoTooLong),
+            // The transaction cannot be decoded: it can only be signed blindly,
+            // if the user allowed it in the settings.
+            Err(_) => {
+                ctx.review_finished = true;
+                let settings: Settings = Default::default();
+                if settings.get_element(SETTING_BLIND_SIGNING) == 0 {
+                    log::debug!("Tx parsing failed and blind signing is disabled");
+                    ui_display_blind_signing_disabled(comm);
+                    return Err(AppSW::TxParsingFail);
                 }
+                log::debug!("Tx parsing failed, showing blind signing review");
+                ui_display_blind_tx(comm, &compute_tx_hash(&ctx.raw_tx), &ctx.path)?
+            }
+        };
+
+        if approved {
+            let response = compute_signature_and_append(comm.begin_response(), ctx)?;
+            if let Some((nonce, value)) = nonce_and_value {
+                record_nonce(&ctx.path, nonce);
+                SpendingPolicy.record(value);
             }
-            // --8<-- [end:ui_bypass]
+            Ok(response)
+        } else {
+            Err(AppSW::Deny)
         }
     }
+    // --8<-- [end:ui_bypass]
 }
 
 fn compute_signature_and_append<'a>(
     mut response: CommandResponse<'a>,
     ctx: &mut TxContext,
 ) -> Result<CommandResponse<'a>, AppSW> {
-    log::debug!("Signing transaction");
-    let mut keccak256 = Keccak256::new();
-    let mut message_hash: [u8; 32] = [0u8; 32];
-
-    let _ = keccak256.hash(&ctx.raw_tx, &mut message_hash);
+    let message_hash = compute_tx_hash(&ctx.raw_tx);
+    append_signature(&mut response, &ctx.path, &message_hash)?;
+    if ctx.return_hash {
+        response.append(&message_hash)?;
+    }
+    if ctx.return_risk {
+        response.append(&[ctx.risks.bits()])?;
+    }
+    Ok(response)
+}
 
-    let (sig, siglen, parity) = Secp256k1::derive_from_path(ctx.path.as_ref())
-        .deterministic_sign(&message_hash)
+/// Signs a transaction hash with the key at `path`, and appends the length of the DER
+/// signature, the signature and the parity of its Y coordinate to the response.
+pub fn append_signature(
+    response: &mut CommandResponse<'_>,
+    path: &Bip32Path,
+    message_hash: &[u8; 32],
+) -> Result<(), AppSW> {
+    log::debug!("Signing transaction");
+    let (sig, siglen, parity) = Secp256k1::derive_from_path(path.as_ref())
+        .deterministic_sign(message_hash)
         .map_err(|_| AppSW::TxSignFail)?;
 
     response
         .append(&[siglen as u8])?
         .append(&sig[..siglen as usize])?
         .append(&[parity as u8])?;
-    Ok(response)
+    Ok(())
+}
+
+/// Checks the transaction nonce against the replay guard, if enabled in the settings.
+///
+/// Returns false if the nonce was already used for this account and the user refused to
+/// continue after the warning. Disabling the setting resets the replay guard, see
+/// [`crate::app_ui::menu::apply_settings`].
+pub fn check_replay(comm: &mut Comm, path: &Bip32Path, nonce: u64) -> bool {
+    let settings: Settings = Default::default();
+    if settings.get_element(SETTING_REPLAY_GUARD) == 0 {
+        return true;
+    }
+    match ReplayGuard.last_nonce(path) {
+        Some(last_nonce) if nonce <= last_nonce => {
+            log::debug!("Nonce already used for this account");
+            ui_display_replay_warning(comm, nonce, last_nonce)
+        }
+        _ => true,
+    }
+}
+
+/// Records the nonce of a signed transaction in the replay guard, if enabled in the settings.
+pub fn record_nonce(path: &Bip32Path, nonce: u64) {
+    let settings: Settings = Default::default();
+    if settings.get_element(SETTING_REPLAY_GUARD) != 0 {
+        ReplayGuard.record(path, nonce);
+    }
+}
+
+/// Compute the Keccak256 hash of a raw transaction, which is the message actually signed.
+pub fn compute_tx_hash(raw_tx: &[u8]) -> [u8; 32] {
+    let mut keccak256 = Keccak256::new();
+    let mut message_hash: [u8; 32] = [0u8; 32];
+    let _ = keccak256.hash(raw_tx, &mut message_hash);
+    message_hash
 }
@@ -0,0 +1,131 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use crate::app_ui::sign::{
+    ui_display_batch, ui_display_risk_warning, ui_display_spending_limit_refused,
+    ui_display_spending_limit_warning,
+};
+use crate::handlers::sign_tx::{
+    append_signature, check_replay, compute_tx_hash, record_nonce, TxContext,
+};
+use crate::spending_policy::{LimitCheck, SpendingPolicy};
+use crate::utils::get_address_from_path;
+use crate::AppSW;
+use ledger_device_sdk::io::{Command, CommandResponse};
+use ledger_device_sdk::log;
+
+/// Handler for the SIGN_TX_BATCH APDU command.
+///
+/// Receives the derivation path of the signing key in the first APDU, then one transaction per
+/// APDU, see [`Batch::add`](crate::batch::Batch::add) for the checks made on each of them.
+/// Transactions received out of sequence are refused with [`AppSW::BadState`].
+///
+/// Once the last transaction is received, a single review summarizes the batch. If the user
+/// approves it, the response holds the number of transactions, and their signatures are read
+/// with GET_BATCH_SIGNATURE.
+///
+/// The "Replay protection" setting and the risk assessment apply to each transaction, the
+/// "Spending limits" setting to the total value of the batch, whatever the coin, as for a
+/// single transaction.
+/// Batches are not available in swap mode.
+pub fn handler_sign_tx_batch<'a>(
+    command: Command<'a>,
+    index: u8,
+    more: bool,
+    ctx: &mut TxContext,
+) -> Result<CommandResponse<'a>, AppSW> {
+    log::debug!("=> handler_sign_tx_batch");
+    if ctx.swap_params.is_some() {
+        return Err(AppSW::InsNotSupported);
+    }
+    let data = command.get_data();
+    ctx.review_finished = false;
+    if index == 0 {
+        ctx.batch.start(data.try_into()?);
+        return Ok(command.into_response());
+    }
+    let path = match ctx.batch.path() {
+        Some(path) if index as usize == ctx.batch.count() + 1 => path,
+        _ => return Err(AppSW::BadState),
+    };
+    let from = get_address_from_path(path)?;
+    ctx.batch.add(data, &from)?;
+    if more {
+        return Ok(command.into_response());
+    }
+
+    log::debug!("Last transaction received, showing batch review");
+    // Every check that may fail was made when the transactions were added, so that an error never
+    // interrupts the warnings or the review. The warnings of the risks raised by any of the
+    // transactions are shown once.
+    let path = ctx.batch.path().ok_or(AppSW::BadState)?;
+    let comm = command.into_comm();
+    ctx.review_finished = true;
+    let within_limits = match SpendingPolicy.check(ctx.batch.total()) {
+        LimitCheck::Allowed => true,
+        LimitCheck::Warn => ui_display_spending_limit_warning(comm),
+        LimitCheck::Refuse => {
+            log::debug!("Batch over the spending limits, refusing");
+            ui_display_spending_limit_refused(comm);
+            return Err(AppSW::SpendingLimitExceeded);
+        }
+    };
+    // Each page is only shown if the user accepted the previous ones
+    let approved = within_limits
+        && ctx
+            .batch
+            .risks()
+            .iter()
+            .all(|risk| ui_display_risk_warning(comm, risk))
+        && ctx
+            .batch
+            .txs()
+            .iter()
+            .all(|tx| check_replay(comm, path, tx.nonce()))
+        && ui_display_batch(comm, &ctx.batch);
+
+    if !approved {
+        return Err(AppSW::Deny);
+    }
+    ctx.batch.approve();
+    if let Some(path) = ctx.batch.path() {
+        for tx in ctx.batch.txs() {
+            record_nonce(path, tx.nonce());
+        }
+    }
+    SpendingPolicy.record(ctx.batch.total());
+
+    let mut response = comm.begin_response();
+    response.append(&[ctx.batch.count() as u8])?;
+    Ok(response)
+}
+
+/// Handler for the GET_BATCH_SIGNATURE APDU command.
+///
+/// Returns the signature of the transaction at `index` of the approved batch, in the format of
+/// the SIGN_TX response.
+pub fn handler_get_batch_signature(
+    command: Command<'_>,
+    index: u8,
+    ctx: &mut TxContext,
+) -> Result<CommandResponse<'_>, AppSW> {
+    log::debug!("=> handler_get_batch_signature");
+    let raw_tx = ctx.batch.approved_tx(index as usize)?;
+    let path = ctx.batch.path().ok_or(AppSW::BadState)?;
+    let mut response = command.into_response();
+    append_signature(&mut response, path, &compute_tx_hash(raw_tx))?;
+    Ok(response)
+}
@@ -0,0 +1,132 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+//! APDU instructions
+//!
+//! Decoding of the INS, P1 and P2 bytes of the APDUs received from the host.
+
+use crate::AppSW;
+use ledger_device_sdk::io::ApduHeader;
+
+// P2 for last APDU to receive.
+const P2_SIGN_TX_LAST: u8 = 0x00;
+// P2 for more APDU to receive.
+const P2_SIGN_TX_MORE: u8 = 0x80;
+// P2 flag of the first APDU, to get the transaction hash with the signature.
+const P2_SIGN_TX_RETURN_HASH: u8 = 0x01;
+// P2 flag of the first APDU, to get the risk flags with the signature.
+const P2_SIGN_TX_RETURN_RISK: u8 = 0x02;
+// P2 flags accepted on the first APDU.
+const P2_SIGN_TX_FLAGS: u8 = P2_SIGN_TX_RETURN_HASH | P2_SIGN_TX_RETURN_RISK;
+// P1 for first APDU number.
+const P1_SIGN_TX_START: u8 = 0x00;
+// P1 for maximum APDU number.
+const P1_SIGN_TX_MAX: u8 = 0x03;
+
+/// Possible input commands received through APDUs.
+#[derive(Debug)]
+pub enum Instruction {
+    GetVersion,
+    GetAppName,
+    GetPubkey {
+        display: bool,
+    },
+    SignTx {
+        chunk: u8,
+        more: bool,
+        return_hash: bool,
+        return_risk: bool,
+    },
+    SetSpendingPolicy,
+    AddAddressBookEntry,
+    ProvideTrustedName,
+    ProvideTokenInfo,
+    ParseTx {
+        chunk: u8,
+        more: bool,
+    },
+    SignTxBatch {
+        index: u8,
+        more: bool,
+    },
+    GetBatchSignature {
+        index: u8,
+    },
+    RemoveAddressBookEntry,
+}
+
+impl TryFrom<ApduHeader> for Instruction {
+    type Error = AppSW;
+
+    /// APDU parsing logic.
+    ///
+    /// Parses INS, P1 and P2 bytes to build an [`Instruction`]. P1 and P2 are translated to
+    /// strongly typed variables depending on the APDU instruction code. Invalid INS, P1 or P2
+    /// values result in errors with a status word, which are automatically sent to the host by the
+    /// SDK.
+    ///
+    /// This design allows a clear separation of the APDU parsing logic and commands handling.
+    ///
+    /// Note that CLA is not checked here. Instead the method `Comm::set_expected_cla` is used in
+    /// `sample_main` to have this verification automatically performed by the SDK.
+    fn try_from(value: ApduHeader) -> Result<Self, Self::Error> {
+        match (value.ins, value.p1, value.p2) {
+            (3, 0, 0) => Ok(Instruction::GetVersion),
+            (4, 0, 0) => Ok(Instruction::GetAppName),
+            (5, 0 | 1, 0) => Ok(Instruction::GetPubkey {
+                display: value.p1 != 0,
+            }),
+            (6, P1_SIGN_TX_START, p2) if p2 & !P2_SIGN_TX_FLAGS == P2_SIGN_TX_MORE => {
+                Ok(Instruction::SignTx {
+                    chunk: value.p1,
+                    more: true,
+                    return_hash: p2 & P2_SIGN_TX_RETURN_HASH != 0,
+                    return_risk: p2 & P2_SIGN_TX_RETURN_RISK != 0,
+                })
+            }
+            (6, 1..=P1_SIGN_TX_MAX, P2_SIGN_TX_LAST | P2_SIGN_TX_MORE) => Ok(Instruction::SignTx {
+                chunk: value.p1,
+                more: value.p2 == P2_SIGN_TX_MORE,
+                return_hash: false,
+                return_risk: false,
+            }),
+            (7, 0, 0) => Ok(Instruction::SetSpendingPolicy),
+            (8, 0, 0) => Ok(Instruction::AddAddressBookEntry),
+            (9, 0, 0) => Ok(Instruction::ProvideTrustedName),
+            (10, 0, 0) => Ok(Instruction::ProvideTokenInfo),
+            // Same chunks as SIGN_TX, without the transaction hash flag
+            (11, P1_SIGN_TX_START, P2_SIGN_TX_MORE)
+            | (11, 1..=P1_SIGN_TX_MAX, P2_SIGN_TX_LAST | P2_SIGN_TX_MORE) => {
+                Ok(Instruction::ParseTx {
+                    chunk: value.p1,
+                    more: value.p2 == P2_SIGN_TX_MORE,
+                })
+            }
+            // The derivation path, then one transaction per APDU. The batch size is checked by
+            // the handler.
+            (12, P1_SIGN_TX_START, P2_SIGN_TX_MORE)
+            | (12, 1.., P2_SIGN_TX_LAST | P2_SIGN_TX_MORE) => Ok(Instruction::SignTxBatch {
+                index: value.p1,
+                more: value.p2 == P2_SIGN_TX_MORE,
+            }),
+            (13, _, 0) => Ok(Instruction::GetBatchSignature { index: value.p1 }),
+            (14, 0, 0) => Ok(Instruction::RemoveAddressBookEntry),
+            (3..=14, _, _) => Err(AppSW::WrongP1P2),
+            (_, _, _) => Err(AppSW::InsNotSupported),
+        }
+    }
+}
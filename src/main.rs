@@ -18,29 +18,52 @@
 #![no_std]
 #![no_main]
 
+mod app_ui;
 mod utils;
-mod app_ui {
-    pub mod address;
-    pub mod menu;
-    pub mod sign;
-}
 mod handlers {
+    pub mod add_address_book_entry;
     pub mod get_public_key;
     pub mod get_version;
+    pub mod parse_tx;
+    pub mod provide_token_info;
+    pub mod provide_trusted_name;
+    pub mod remove_address_book_entry;
+    pub mod set_spending_policy;
     pub mod sign_tx;
+    pub mod sign_tx_batch;
 }
 
+mod address_book;
+mod batch;
+mod instruction;
+mod replay_guard;
+mod risk;
 mod settings;
+mod spending_policy;
+mod status_words;
+mod strings;
 mod swap;
+mod token;
+mod trusted_name;
+mod tx;
 
-use app_ui::menu::ui_menu_main;
+use app_ui::menu::{apply_settings, ui_menu_main};
 use handlers::{
+    add_address_book_entry::handler_add_address_book_entry,
     get_public_key::handler_get_public_key,
     get_version::handler_get_version,
+    parse_tx::handler_parse_tx,
+    provide_token_info::handler_provide_token_info,
+    provide_trusted_name::handler_provide_trusted_name,
+    remove_address_book_entry::handler_remove_address_book_entry,
+    set_spending_policy::handler_set_spending_policy,
     sign_tx::{handler_sign_tx, TxContext},
+    sign_tx_batch::{handler_get_batch_signature, handler_sign_tx_batch},
 };
-use ledger_device_sdk::io::{self, init_comm, ApduHeader, Comm, Command, Reply, StatusWords};
+use instruction::Instruction;
+use ledger_device_sdk::io::{self, init_comm, Comm, Command, Reply};
 use ledger_device_sdk::libcall::swap::CreateTxParams;
+use status_words::AppSW;
 
 ledger_device_sdk::set_panic!(ledger_device_sdk::exiting_panic);
 
@@ -51,88 +74,23 @@ use ledger_device_sdk::nbgl::{NbglReviewStatus, StatusType};
 
 ledger_device_sdk::define_comm!(COMM);
 
-// P2 for last APDU to receive.
-const P2_SIGN_TX_LAST: u8 = 0x00;
-// P2 for more APDU to receive.
-const P2_SIGN_TX_MORE: u8 = 0x80;
-// P1 for first APDU number.
-const P1_SIGN_TX_START: u8 = 0x00;
-// P1 for maximum APDU number.
-const P1_SIGN_TX_MAX: u8 = 0x03;
-
-// Application status words.
-#[repr(u16)]
-#[derive(Clone, Copy, PartialEq)]
-pub enum AppSW {
-    Deny = 0x6985,
-    WrongP1P2 = 0x6A86,
-    InsNotSupported = 0x6D00,
-    ClaNotSupported = 0x6E00,
-    CommError = 0x6F00,
-    TxDisplayFail = 0xB001,
-    AddrDisplayFail = 0xB002,
-    TxWrongLength = 0xB004,
-    TxParsingFail = 0xB005,
-    TxHashFail = 0xB006,
-    TxSignFail = 0xB008,
-    KeyDeriveFail = 0xB009,
-    VersionParsingFail = 0xB00A,
-    WrongApduLength = StatusWords::BadLen as u16,
-    SwapFail = 0xC000,
-    Ok = 0x9000,
-}
-
 impl From<AppSW> for Reply {
     fn from(sw: AppSW) -> Reply {
         Reply(sw as u16)
     }
 }
 
-impl From<io::CommError> for AppSW {
-    fn from(_e: io::CommError) -> Self {
-        AppSW::CommError
-    }
-}
+// The SDK answers malformed APDUs with its own status words: the application uses the same
+// values for the same errors.
+const _: () = assert!(AppSW::WrongApduLength as u16 == io::StatusWords::BadLen as u16);
+const _: () = assert!(AppSW::ClaNotSupported as u16 == io::StatusWords::BadCla as u16);
 
-/// Possible input commands received through APDUs.
-#[derive(Debug)]
-pub enum Instruction {
-    GetVersion,
-    GetAppName,
-    GetPubkey { display: bool },
-    SignTx { chunk: u8, more: bool },
-}
-
-impl TryFrom<ApduHeader> for Instruction {
-    type Error = AppSW;
-
-    /// APDU parsing logic.
-    ///
-    /// Parses INS, P1 and P2 bytes to build an [`Instruction`]. P1 and P2 are translated to
-    /// strongly typed variables depending on the APDU instruction code. Invalid INS, P1 or P2
-    /// values result in errors with a status word, which are automatically sent to the host by the
-    /// SDK.
-    ///
-    /// This design allows a clear separation of the APDU parsing logic and commands handling.
-    ///
-    /// Note that CLA is not checked here. Instead the method [`Comm::set_expected_cla`] is used in
-    /// [`sample_main`] to have this verification automatically performed by the SDK.
-    fn try_from(value: ApduHeader) -> Result<Self, Self::Error> {
-        match (value.ins, value.p1, value.p2) {
-            (3, 0, 0) => Ok(Instruction::GetVersion),
-            (4, 0, 0) => Ok(Instruction::GetAppName),
-            (5, 0 | 1, 0) => Ok(Instruction::GetPubkey {
-                display: value.p1 != 0,
-            }),
-            (6, P1_SIGN_TX_START, P2_SIGN_TX_MORE)
-            | (6, 1..=P1_SIGN_TX_MAX, P2_SIGN_TX_LAST | P2_SIGN_TX_MORE) => {
-                Ok(Instruction::SignTx {
-                    chunk: value.p1,
-                    more: value.p2 == P2_SIGN_TX_MORE,
-                })
-            }
-            (3..=6, _, _) => Err(AppSW::WrongP1P2),
-            (_, _, _) => Err(AppSW::InsNotSupported),
+impl From<io::CommError> for AppSW {
+    fn from(e: io::CommError) -> Self {
+        match e {
+            // The response does not fit in the APDU buffer
+            io::CommError::Overflow => AppSW::WrongResponseLength,
+            io::CommError::IoError => AppSW::CommError,
         }
     }
 }
@@ -150,9 +108,30 @@ fn show_status_and_home_if_needed(
         (Instruction::GetPubkey { display: true }, AppSW::Deny | AppSW::Ok) => {
             (true, StatusType::Address)
         }
-        (Instruction::SignTx { .. }, AppSW::Deny | AppSW::Ok) if tx_ctx.finished() => {
+        (Instruction::SignTx { .. } | Instruction::SignTxBatch { .. }, AppSW::Deny | AppSW::Ok)
+            if tx_ctx.finished() =>
+        {
             (true, StatusType::Transaction)
         }
+        (
+            Instruction::SetSpendingPolicy
+            | Instruction::AddAddressBookEntry
+            | Instruction::RemoveAddressBookEntry,
+            AppSW::Deny | AppSW::Ok,
+        ) => (true, StatusType::Operation),
+        // Any error returned once a review has started only goes back to the home screen, e.g.
+        // the refusal of a transaction that cannot be blind signed, or that is over the spending
+        // limits, which has already been explained on screen.
+        (
+            Instruction::SignTx { .. }
+            | Instruction::SignTxBatch { .. }
+            | Instruction::AddAddressBookEntry
+            | Instruction::RemoveAddressBookEntry,
+            _,
+        ) if tx_ctx.finished() => {
+            tx_ctx.home.show_and_return();
+            return;
+        }
         (_, _) => (false, StatusType::Transaction),
     };
 
@@ -205,6 +184,7 @@ pub fn normal_main(swap_params: Option<&CreateTxParams>) -> bool {
         TxContext::new()
     };
 
+    apply_settings();
     if swap_params.is_none() {
         tx_ctx.home = ui_menu_main(comm);
         tx_ctx.home.show_and_return();
@@ -212,6 +192,8 @@ pub fn normal_main(swap_params: Option<&CreateTxParams>) -> bool {
 
     loop {
         let command = comm.next_command();
+        // The settings may have been changed while waiting for the command
+        apply_settings();
         let decoded = command.decode::<Instruction>();
         let Ok(ins) = decoded else {
             let _ = comm.send(&[], decoded.unwrap_err());
@@ -250,6 +232,23 @@ fn handle_apdu<'a>(
         }
         Instruction::GetVersion => handler_get_version(command),
         Instruction::GetPubkey { display } => handler_get_public_key(command, *display),
-        Instruction::SignTx { chunk, more } => handler_sign_tx(command, *chunk, *more, ctx),
+        Instruction::SignTx {
+            chunk,
+            more,
+            return_hash,
+            return_risk,
+        } => handler_sign_tx(command, *chunk, *more, *return_hash, *return_risk, ctx),
+        Instruction::SetSpendingPolicy => handler_set_spending_policy(command),
+        Instruction::AddAddressBookEntry => handler_add_address_book_entry(command, ctx),
+        Instruction::ProvideTrustedName => handler_provide_trusted_name(command, ctx),
+        Instruction::ProvideTokenInfo => handler_provide_token_info(command, ctx),
+        Instruction::ParseTx { chunk, more } => handler_parse_tx(command, *chunk, *more, ctx),
+        Instruction::SignTxBatch { index, more } => {
+            handler_sign_tx_batch(command, *index, *more, ctx)
+        }
+        Instruction::GetBatchSignature { index } => {
+            handler_get_batch_signature(command, *index, ctx)
+        }
+        Instruction::RemoveAddressBookEntry => handler_remove_address_book_entry(command, ctx),
     }
 }
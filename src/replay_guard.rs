@@ -0,0 +1,180 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+//! Replay guard
+//!
+//! Keeps track, in NVM, of the highest nonce signed by each account so that a transaction
+//! reusing an already signed nonce can be detected before it is reviewed.
+//!
+//! The table is bounded to [`REPLAY_GUARD_ENTRIES`] accounts. When it is full, the least
+//! recently used account is evicted. Accounts are identified by the Keccak256 hash of their
+//! BIP32 path.
+
+use ledger_device_sdk::hash::{sha3::Keccak256, HashInit};
+use ledger_device_sdk::nvm::*;
+use ledger_device_sdk::NVMData;
+
+use crate::utils::Bip32Path;
+
+/// Maximum number of accounts tracked by the replay guard.
+pub const REPLAY_GUARD_ENTRIES: usize = 16;
+
+#[derive(Clone, Copy)]
+struct NonceEntry {
+    /// Keccak256 hash of the account BIP32 path.
+    path_hash: [u8; 32],
+    /// Highest nonce signed for this account.
+    nonce: u64,
+    /// Value of the table clock when the entry was last used, 0 for an empty entry.
+    last_used: u32,
+}
+
+#[derive(Clone, Copy)]
+struct NonceTable {
+    entries: [NonceEntry; REPLAY_GUARD_ENTRIES],
+    /// Monotonic counter used to order entries for LRU eviction.
+    clock: u32,
+}
+
+const EMPTY_ENTRY: NonceEntry = NonceEntry {
+    path_hash: [0u8; 32],
+    nonce: 0,
+    last_used: 0,
+};
+
+const EMPTY_TABLE: NonceTable = NonceTable {
+    entries: [EMPTY_ENTRY; REPLAY_GUARD_ENTRIES],
+    clock: 0,
+};
+
+// This is necessary to store the object in NVM and not in RAM
+#[link_section = ".nvm_data"]
+static mut TABLE: NVMData<AtomicStorage<NonceTable>> =
+    NVMData::new(AtomicStorage::new(&EMPTY_TABLE));
+
+#[derive(Clone, Copy, Default)]
+pub struct ReplayGuard;
+
+impl ReplayGuard {
+    /// Returns the highest nonce signed so far for the account at `path`, if any.
+    pub fn last_nonce(&self, path: &Bip32Path) -> Option<u64> {
+        let path_hash = hash_path(path);
+        self.table()
+            .entries
+            .iter()
+            .find(|entry| entry.last_used != 0 && entry.path_hash == path_hash)
+            .map(|entry| entry.nonce)
+    }
+
+    /// Records that `nonce` has been signed for the account at `path`.
+    ///
+    /// The stored nonce never decreases. If the account is not tracked yet and the table is
+    /// full, the least recently used entry is replaced.
+    pub fn record(&self, path: &Bip32Path, nonce: u64) {
+        let path_hash = hash_path(path);
+        let mut table = *self.table();
+
+        // Restart the clock from a compacted state instead of wrapping around, so that the
+        // ordering of entries is preserved.
+        if table.clock == u32::MAX {
+            compact_clock(&mut table);
+        }
+        table.clock += 1;
+
+        let index = match table
+            .entries
+            .iter()
+            .position(|entry| entry.last_used != 0 && entry.path_hash == path_hash)
+        {
+            Some(index) => index,
+            None => {
+                // Empty entries have `last_used == 0`, hence are picked first.
+                let index = table
+                    .entries
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(index, _)| index)
+                    .unwrap_or(0);
+                table.entries[index] = NonceEntry {
+                    path_hash,
+                    nonce,
+                    last_used: 0,
+                };
+                index
+            }
+        };
+
+        let entry = &mut table.entries[index];
+        entry.nonce = entry.nonce.max(nonce);
+        entry.last_used = table.clock;
+
+        self.update(&table);
+    }
+
+    /// Returns true if no nonce has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.table()
+            .entries
+            .iter()
+            .all(|entry| entry.last_used == 0)
+    }
+
+    /// Forgets every recorded nonce.
+    pub fn reset(&self) {
+        self.update(&EMPTY_TABLE);
+    }
+
+    fn table(&self) -> &NonceTable {
+        let data = &raw const TABLE;
+        let storage = unsafe { (*data).get_ref() };
+        storage.get_ref()
+    }
+
+    fn update(&self, table: &NonceTable) {
+        let data = &raw mut TABLE;
+        let storage = unsafe { (*data).get_mut() };
+        unsafe {
+            storage.update(table);
+        }
+    }
+}
+
+/// Renumbers the `last_used` values of the tracked entries from 1, keeping their order.
+fn compact_clock(table: &mut NonceTable) {
+    let mut clock = 0;
+    while let Some(entry) = table
+        .entries
+        .iter_mut()
+        .filter(|entry| entry.last_used > clock)
+        .min_by_key(|entry| entry.last_used)
+    {
+        clock += 1;
+        entry.last_used = clock;
+    }
+    table.clock = clock;
+}
+
+fn hash_path(path: &Bip32Path) -> [u8; 32] {
+    let mut keccak256 = Keccak256::new();
+    let mut path_hash = [0u8; 32];
+    for component in path.as_ref() {
+        let _ = keccak256.update(&component.to_be_bytes());
+    }
+    let _ = keccak256.finalize(&mut path_hash);
+    path_hash
+}
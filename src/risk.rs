@@ -0,0 +1,103 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+//! Risk assessment
+//!
+//! Conditions of a decoded transaction that deserve the attention of the user, assessed between
+//! its parsing and its review. Each raised flag adds a warning page before the review, and the
+//! flags can be returned to the host with the signature, for logging.
+
+use crate::spending_policy::SpendingPolicy;
+use crate::token::TokenInfo;
+use crate::tx::Tx;
+
+/// Condition flagged by the risk assessment. The values are the bits of the flags returned to
+/// the host.
+#[repr(u8)]
+#[derive(Clone, Copy)]
+pub enum Risk {
+    /// The destination is the address of the signing key.
+    SelfTransfer = 0x01,
+    /// The transaction transfers nothing.
+    ZeroValue = 0x02,
+    /// The transferred token has no verified descriptor.
+    UnverifiedToken = 0x04,
+    /// The value is above the high value threshold of the spending policy.
+    HighValue = 0x08,
+}
+
+impl Risk {
+    /// All the risks, in the order of their warning pages.
+    const ALL: [Risk; 4] = [
+        Risk::SelfTransfer,
+        Risk::ZeroValue,
+        Risk::UnverifiedToken,
+        Risk::HighValue,
+    ];
+}
+
+/// Set of [`Risk`] flags.
+#[derive(Clone, Copy, Default)]
+pub struct RiskFlags(u8);
+
+impl RiskFlags {
+    /// Adds `risk` to the set.
+    pub fn insert(&mut self, risk: Risk) {
+        self.0 |= risk as u8;
+    }
+
+    /// Returns true if `risk` is in the set.
+    pub fn contains(self, risk: Risk) -> bool {
+        self.0 & risk as u8 != 0
+    }
+
+    /// Flags in the set, in the order of their warning pages.
+    pub fn iter(self) -> impl Iterator<Item = Risk> {
+        Risk::ALL
+            .into_iter()
+            .filter(move |risk| self.contains(*risk))
+    }
+
+    /// Flags in the format of the SIGN_TX response.
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+}
+
+/// Assesses the risks of a decoded transaction.
+///
+/// # Arguments
+///
+/// * `tx` - Transaction to be reviewed
+/// * `from` - Address of the signing key
+/// * `token` - Descriptor of the transferred token, if it is a verified token transfer
+pub fn assess(tx: &Tx, from: &[u8; 20], token: Option<&TokenInfo>) -> RiskFlags {
+    let mut flags = RiskFlags::default();
+    if *from == tx.to {
+        flags.insert(Risk::SelfTransfer);
+    }
+    if tx.value == 0 {
+        flags.insert(Risk::ZeroValue);
+    }
+    match tx.token {
+        Some(_) if token.is_none() => flags.insert(Risk::UnverifiedToken),
+        // The threshold is expressed in the native coin, as the spending limits
+        None if SpendingPolicy.is_high_value(tx.value) => flags.insert(Risk::HighValue),
+        _ => (),
+    }
+    flags
+}
@@ -3,6 +3,15 @@ use ledger_device_sdk::NVMData;
 
 // This is necessary to store the object in NVM and not in RAM
 const SETTINGS_SIZE: usize = 10;
+
+// Index of each switch in the settings storage. The order must match the
+// settings strings given to `NbglHomeAndSettings` in `ui_menu_main`.
+pub const SETTING_DISPLAY_MEMO: usize = 0;
+pub const SETTING_BLIND_SIGNING: usize = 1;
+pub const SETTING_EXPERT_MODE: usize = 2;
+pub const SETTING_REPLAY_GUARD: usize = 3;
+pub const SETTING_SPENDING_LIMITS: usize = 4;
+pub const SETTING_ADDRESS_BOOK: usize = 5;
 #[link_section = ".nvm_data"]
 static mut DATA: NVMData<AtomicStorage<[u8; SETTINGS_SIZE]>> =
     NVMData::new(AtomicStorage::new(&[0u8; SETTINGS_SIZE]));
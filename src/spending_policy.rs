@@ -0,0 +1,225 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+//! Spending policy
+//!
+//! On-device spending limits, checked against every transaction before it is signed.
+//!
+//! The limits are set with the SET_SPENDING_POLICY APDU, after confirmation on the device, and
+//! only enforced while the "Spending limits" setting is enabled. The policy holds:
+//! - a maximum `value` per transaction,
+//! - an optional rolling cumulative limit on the sum of the values of the last
+//!   [`CUMULATIVE_WINDOW`] signed transactions, the one being checked included. The device has
+//!   no clock, so the window is counted in signatures rather than in time. It is emptied each
+//!   time a policy is set. Transactions of a zero native value, such as token transfers, are
+//!   neither checked against it nor counted in it, and a batch counts as one transaction of its
+//!   total value,
+//! - the action taken on a transaction over a limit: warn the user or refuse it,
+//! - an optional high value threshold: transactions above it are not over a limit, but are
+//!   flagged by the risk assessment, see [`crate::risk`].
+//!
+//! Swap transactions are never reviewed, so they are always refused when over a limit.
+//!
+//! The limits are configured from the host rather than edited in the settings page: the NBGL
+//! settings page only offers on/off switches, and typing amounts with the buttons of a device is
+//! error prone. The device still has the last word, since every value is shown for approval
+//! before the policy is stored. The "Spending limits" switch of the settings page enables or
+//! disables their enforcement.
+
+use ledger_device_sdk::nvm::*;
+use ledger_device_sdk::NVMData;
+
+use crate::settings::{Settings, SETTING_SPENDING_LIMITS};
+use crate::AppSW;
+
+/// Number of signed transactions over which the cumulative limit applies, the one being checked
+/// included.
+pub const CUMULATIVE_WINDOW: usize = 10;
+
+/// Serialized length of a policy in the SET_SPENDING_POLICY APDU.
+const POLICY_LEN: usize = 17;
+/// Serialized length of a policy with a high value threshold.
+const POLICY_WITH_THRESHOLD_LEN: usize = POLICY_LEN + 8;
+
+/// What to do with a transaction over a spending limit.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LimitAction {
+    /// Show a warning before the review, the user may still sign.
+    Warn = 0x00,
+    /// Refuse to sign.
+    Refuse = 0x01,
+}
+
+#[derive(Clone, Copy)]
+pub struct Policy {
+    /// Maximum value of a single transaction.
+    pub max_per_tx: u64,
+    /// Maximum cumulative value of the last [`CUMULATIVE_WINDOW`] signed transactions, 0 if
+    /// there is no such limit.
+    pub cumulative_limit: u64,
+    pub action: LimitAction,
+    /// Value above which a transaction is flagged as high value, `u64::MAX` if not set.
+    pub high_value_threshold: u64,
+}
+
+impl TryFrom<&[u8]> for Policy {
+    type Error = AppSW;
+
+    /// Constructs a [`Policy`] from APDU-encoded bytes.
+    ///
+    /// # Format
+    ///
+    /// - 8 bytes: Big-endian maximum value per transaction
+    /// - 8 bytes: Big-endian cumulative limit (0 to disable)
+    /// - 1 byte: Action on a transaction over a limit (0: warn, 1: refuse)
+    /// - 8 bytes, optional: Big-endian high value threshold
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        let high_value_threshold = match data.len() {
+            POLICY_LEN => u64::MAX,
+            POLICY_WITH_THRESHOLD_LEN => u64::from_be_bytes(data[17..25].try_into().unwrap()),
+            _ => return Err(AppSW::WrongApduLength),
+        };
+        let action = match data[16] {
+            0x00 => LimitAction::Warn,
+            0x01 => LimitAction::Refuse,
+            _ => return Err(AppSW::PolicyParsingFail),
+        };
+        Ok(Policy {
+            max_per_tx: u64::from_be_bytes(data[0..8].try_into().unwrap()),
+            cumulative_limit: u64::from_be_bytes(data[8..16].try_into().unwrap()),
+            action,
+            high_value_threshold,
+        })
+    }
+}
+
+/// Result of checking a transaction value against the policy.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LimitCheck {
+    /// Within the limits, or limits not enforced.
+    Allowed,
+    /// Over a limit, the user must be warned before the review.
+    Warn,
+    /// Over a limit, the transaction must not be signed.
+    Refuse,
+}
+
+#[derive(Clone, Copy)]
+struct PolicyState {
+    policy: Policy,
+    /// Values of the previous signed transactions of the window, 0 in unused slots.
+    recent: [u64; CUMULATIVE_WINDOW - 1],
+    /// Slot of `recent` overwritten by the next signed transaction, which is the oldest one.
+    next: usize,
+}
+
+impl PolicyState {
+    /// Sum of the values of the previous signed transactions of the window.
+    fn spent(&self) -> u64 {
+        self.recent
+            .iter()
+            .fold(0u64, |spent, value| spent.saturating_add(*value))
+    }
+}
+
+// No limit until the user sets a policy.
+const DEFAULT_STATE: PolicyState = PolicyState {
+    policy: Policy {
+        max_per_tx: u64::MAX,
+        cumulative_limit: 0,
+        action: LimitAction::Warn,
+        high_value_threshold: u64::MAX,
+    },
+    recent: [0; CUMULATIVE_WINDOW - 1],
+    next: 0,
+};
+
+// This is necessary to store the object in NVM and not in RAM
+#[link_section = ".nvm_data"]
+static mut STATE: NVMData<AtomicStorage<PolicyState>> =
+    NVMData::new(AtomicStorage::new(&DEFAULT_STATE));
+
+#[derive(Clone, Copy, Default)]
+pub struct SpendingPolicy;
+
+impl SpendingPolicy {
+    /// Replaces the policy and empties the cumulative window.
+    pub fn set(&self, policy: Policy) {
+        self.update(&PolicyState {
+            policy,
+            ..DEFAULT_STATE
+        });
+    }
+
+    /// Checks a transaction `value` against the policy.
+    pub fn check(&self, value: u64) -> LimitCheck {
+        let settings: Settings = Default::default();
+        if settings.get_element(SETTING_SPENDING_LIMITS) == 0 {
+            return LimitCheck::Allowed;
+        }
+
+        let state = self.state();
+        let over_cumulative = value != 0
+            && state.policy.cumulative_limit != 0
+            && state.spent().saturating_add(value) > state.policy.cumulative_limit;
+        if value <= state.policy.max_per_tx && !over_cumulative {
+            LimitCheck::Allowed
+        } else if state.policy.action == LimitAction::Refuse {
+            LimitCheck::Refuse
+        } else {
+            LimitCheck::Warn
+        }
+    }
+
+    /// Returns true if a transaction `value` is above the high value threshold of the policy.
+    pub fn is_high_value(&self, value: u64) -> bool {
+        let settings: Settings = Default::default();
+        settings.get_element(SETTING_SPENDING_LIMITS) != 0
+            && value > self.state().policy.high_value_threshold
+    }
+
+    /// Adds the `value` of a signed transaction to the cumulative window, in place of the oldest
+    /// one.
+    pub fn record(&self, value: u64) {
+        let settings: Settings = Default::default();
+        let mut state = *self.state();
+        if settings.get_element(SETTING_SPENDING_LIMITS) == 0
+            || state.policy.cumulative_limit == 0
+            || value == 0
+        {
+            return;
+        }
+        state.recent[state.next] = value;
+        state.next = (state.next + 1) % state.recent.len();
+        self.update(&state);
+    }
+
+    fn state(&self) -> &PolicyState {
+        let data = &raw const STATE;
+        let storage = unsafe { (*data).get_ref() };
+        storage.get_ref()
+    }
+
+    fn update(&self, state: &PolicyState) {
+        let data = &raw mut STATE;
+        let storage = unsafe { (*data).get_mut() };
+        unsafe {
+            storage.update(state);
+        }
+    }
+}
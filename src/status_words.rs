@@ -0,0 +1,148 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+//! Status words
+//!
+//! The `status_words!` table is the only definition of the status words of the application.
+//! It generates [`AppSW`] and, from `build.rs`, the `Errors` enum of the Python client in
+//! `tests/application_client/boilerplate_status_words.py`. The swap simulation of
+//! `tests/swap_sim` includes this module, and checks that the Python client is up to date.
+//!
+//! To add a status word, add a line to the table with its documentation, its value and its
+//! name in the Python client, then replace the Python client with the one generated in the
+//! `OUT_DIR` of the application: the build fails until it is up to date.
+
+/// Table of the error status words: documentation, `AppSW` variant, value and name in the
+/// Python client. Passes the table to the `$callback` macro.
+macro_rules! status_words {
+    ($callback:ident) => {
+        $callback! {
+            /// The user rejected the request.
+            Deny = 0x6985 => SW_DENY,
+            /// P1 or P2 is not valid for the instruction.
+            WrongP1P2 = 0x6A86 => SW_WRONG_P1P2,
+            /// The instruction is not supported.
+            InsNotSupported = 0x6D00 => SW_INS_NOT_SUPPORTED,
+            /// The class is not supported. Same value as the SDK `StatusWords::BadCla`, sent by
+            /// the SDK, see `Comm::set_expected_cla`.
+            ClaNotSupported = 0x6E00 => SW_CLA_NOT_SUPPORTED,
+            /// The length of the APDU, or of its data, is not valid. Same value as the SDK
+            /// `StatusWords::BadLen`, sent by the SDK for truncated APDUs (checked in `main.rs`).
+            WrongApduLength = 0x6E03 => SW_WRONG_APDU_LENGTH,
+            /// The APDU exchange with the host failed.
+            CommError = 0x6F00 => SW_COMM_ERROR,
+            /// The response does not fit in the APDU buffer (`CommError::Overflow`).
+            WrongResponseLength = 0xB000 => SW_WRONG_RESPONSE_LENGTH,
+            /// The derivation path could not be displayed. Not sent by this application, kept
+            /// for the clients of the other boilerplate applications.
+            Bip32PathDisplayFail = 0xB001 => SW_DISPLAY_BIP32_PATH_FAIL,
+            /// The address could not be displayed.
+            AddrDisplayFail = 0xB002 => SW_DISPLAY_ADDRESS_FAIL,
+            /// The amount of the transaction could not be displayed: its coin ticker is too long.
+            DisplayAmountFail = 0xB003 => SW_DISPLAY_AMOUNT_FAIL,
+            /// The transaction is longer than `MAX_TRANSACTION_LEN`.
+            TxWrongLength = 0xB004 => SW_WRONG_TX_LENGTH,
+            /// The transaction could not be parsed, and blind signing is disabled.
+            TxParsingFail = 0xB005 => SW_TX_PARSING_FAIL,
+            /// The transaction could not be hashed. Not sent by this application, kept for the
+            /// clients of the other boilerplate applications.
+            TxHashFail = 0xB006 => SW_TX_HASH_FAIL,
+            /// A SIGN_TX chunk was received out of sequence: without the derivation path chunk,
+            /// or not right after the previous chunk.
+            BadState = 0xB007 => SW_BAD_STATE,
+            /// The transaction could not be signed.
+            TxSignFail = 0xB008 => SW_SIGNATURE_FAIL,
+            /// The public key or the chain code could not be derived.
+            KeyDeriveFail = 0xB009 => SW_KEY_DERIVE_FAIL,
+            /// The version of the application could not be parsed.
+            VersionParsingFail = 0xB00A => SW_VERSION_PARSING_FAIL,
+            /// The spending policy could not be parsed.
+            PolicyParsingFail = 0xB00B => SW_POLICY_PARSING_FAIL,
+            /// The transaction is over the spending limits, and the policy refuses it.
+            SpendingLimitExceeded = 0xB00C => SW_SPENDING_LIMIT_EXCEEDED,
+            /// The address book entry could not be parsed.
+            AddressBookEntryInvalid = 0xB00D => SW_ADDRESS_BOOK_ENTRY_INVALID,
+            /// The address book is full.
+            AddressBookFull = 0xB00E => SW_ADDRESS_BOOK_FULL,
+            /// The address book is disabled in the settings.
+            AddressBookDisabled = 0xB00F => SW_ADDRESS_BOOK_DISABLED,
+            /// The trusted name descriptor could not be parsed.
+            TrustedNameInvalid = 0xB010 => SW_TRUSTED_NAME_INVALID,
+            /// The signature of the trusted name descriptor is not valid.
+            TrustedNameSignatureFail = 0xB011 => SW_TRUSTED_NAME_SIGNATURE_FAIL,
+            /// The token descriptor could not be parsed.
+            TokenInfoInvalid = 0xB012 => SW_TOKEN_INFO_INVALID,
+            /// The signature of the token descriptor is not valid.
+            TokenInfoSignatureFail = 0xB013 => SW_TOKEN_INFO_SIGNATURE_FAIL,
+            /// The batch already holds `MAX_BATCH_SIZE` transactions.
+            BatchTooLarge = 0xB014 => SW_BATCH_TOO_LARGE,
+            /// The total value of the batch is over `MAX_BATCH_TOTAL`, or its fees do not fit in
+            /// 64 bits.
+            BatchValueTooHigh = 0xB015 => SW_BATCH_VALUE_TOO_HIGH,
+            /// Token transfers cannot be signed in a batch.
+            BatchTokenTransfer = 0xB016 => SW_BATCH_TOKEN_TRANSFER,
+            /// The memo of the transaction is longer than `MAX_MEMO_LEN`.
+            MemoTooLong = 0xB017 => SW_MEMO_TOO_LONG,
+            /// No contact is saved for the address to remove from the address book.
+            AddressBookEntryNotFound = 0xB018 => SW_ADDRESS_BOOK_ENTRY_NOT_FOUND,
+            // 0xB019 refused the batches mixing coins, which now have a total per coin.
+            /// The transactions of a batch must have distinct nonces.
+            BatchDuplicateNonce = 0xB01A => SW_BATCH_DUPLICATE_NONCE,
+            /// The label of the address book entry is already saved, for the same or another
+            /// address.
+            AddressBookDuplicate = 0xB01B => SW_ADDRESS_BOOK_DUPLICATE,
+            /// The token address of the transaction is not a hex encoded address.
+            TokenAddressInvalid = 0xB01C => SW_TOKEN_ADDRESS_INVALID,
+            /// The transaction does not match the swap parameters. The swap error is in the
+            /// response data.
+            SwapFail = 0xC000 => SW_SWAP_FAIL,
+        }
+    };
+}
+
+/// Defines [`AppSW`] from the table.
+macro_rules! define_app_sw {
+    ($($(#[doc = $doc:literal])+ $name:ident = $value:literal => $client:ident,)+) => {
+        /// Application status words.
+        #[repr(u16)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum AppSW {
+            $($(#[doc = $doc])+ $name = $value,)+
+            /// Success.
+            Ok = 0x9000,
+        }
+    };
+}
+
+/// Expands to the source of the Python module of the status words, as a `&'static str`.
+#[allow(unused_macros)]
+macro_rules! python_client {
+    ($($(#[doc = $doc:literal])+ $name:ident = $value:literal => $client:ident,)+) => {
+        concat!(
+            "# Status words of the application.\n",
+            "# Generated by build.rs from src/status_words.rs, do not edit.\n",
+            "\n",
+            "from enum import IntEnum\n",
+            "\n",
+            "\n",
+            "class Errors(IntEnum):\n",
+            $($("    #", $doc, "\n",)+ "    ", stringify!($client), " = ", stringify!($value), "\n",)+
+        )
+    };
+}
+
+status_words!(define_app_sw);
@@ -0,0 +1,453 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+//! On-device strings
+//!
+//! Every text shown on the device is read from [`STRINGS`], the table of the language selected
+//! at build time: English by default, French with the `lang_fr` feature.
+//!
+//! Each table is checked at compile time against the length budgets below, whatever the selected
+//! language, so that a translation much longer than the English text does not build. The budgets
+//! are not derived from the NBGL layouts, whose room depends on the device and the font: they are
+//! set from the longest English texts, and a new or longer text must still be checked on every
+//! device with the snapshots of the functional tests. The NBGL fonts only have glyphs for
+//! printable ASCII characters: translations are written without accents, which is also checked.
+//! Lengths are counted in characters, line by line. To add a language, add a table and check it
+//! with [`check`].
+
+/// Longest line of a review title, status or warning text.
+const MAX_LINE_LEN: usize = 32;
+/// Most lines of a review title, status or warning text.
+const MAX_LINES: usize = 3;
+/// Longest field name, warning title, button label or word inserted in a field value.
+const MAX_LABEL_LEN: usize = 24;
+/// Longest setting name.
+const MAX_SETTING_NAME_LEN: usize = 24;
+/// Longest setting description, which NBGL wraps on its own.
+const MAX_SETTING_DESCRIPTION_LEN: usize = 80;
+
+/// Number of switches in the settings page.
+pub const SETTINGS_COUNT: usize = 6;
+
+/// Strings of one language.
+pub struct Strings {
+    // Titles of the transaction reviews
+    pub review_tx: &'static str,
+    pub sign_tx: &'static str,
+    pub sign_blind_tx: &'static str,
+    pub review_batch: &'static str,
+    pub sign_batch: &'static str,
+
+    // Fields of the transaction reviews
+    pub amount: &'static str,
+    pub fees: &'static str,
+    pub token: &'static str,
+    pub destination: &'static str,
+    pub memo: &'static str,
+    pub nonce: &'static str,
+    pub path: &'static str,
+    pub tx_hash: &'static str,
+    pub from: &'static str,
+    pub transactions: &'static str,
+    pub total: &'static str,
+
+    // Words inserted in the field values
+    pub verified: &'static str,
+    pub unverified: &'static str,
+    pub account: &'static str,
+    pub non_ascii_memo: &'static str,
+
+    // Refusal statuses
+    pub blind_signing_disabled: &'static str,
+    pub spending_limit_refused: &'static str,
+
+    // Warnings shown before a review, as a title and a text
+    pub continue_anyway: &'static str,
+    pub reject_tx: &'static str,
+    pub replay: &'static str,
+    /// Takes the nonce of the transaction, then the last signed nonce, see [`fill`].
+    pub replay_text: &'static str,
+    pub spending_limit: &'static str,
+    pub spending_limit_text: &'static str,
+    pub self_transfer: &'static str,
+    pub self_transfer_text: &'static str,
+    pub zero_value: &'static str,
+    pub zero_value_text: &'static str,
+    pub unverified_token: &'static str,
+    pub unverified_token_text: &'static str,
+    pub high_value: &'static str,
+    pub high_value_text: &'static str,
+
+    // Address verification
+    pub verify_address: &'static str,
+
+    // Address book entry review
+    pub review_contact: &'static str,
+    pub save_contact: &'static str,
+    pub review_contact_removal: &'static str,
+    pub remove_contact: &'static str,
+    pub label: &'static str,
+    pub address: &'static str,
+
+    // Spending policy review
+    pub review_policy: &'static str,
+    pub set_policy: &'static str,
+    pub max_per_tx: &'static str,
+    pub cumulative_limit: &'static str,
+    pub cumulative_window: &'static str,
+    pub over_limit: &'static str,
+    pub high_value_above: &'static str,
+    pub no_limit: &'static str,
+    pub warn: &'static str,
+    pub refuse: &'static str,
+
+    /// Names and descriptions of the switches of the settings page, in the order of
+    /// `crate::settings`.
+    pub settings: [[&'static str; 2]; SETTINGS_COUNT],
+}
+
+/// English strings.
+pub const EN: Strings = Strings {
+    review_tx: "Review transaction\nto send CRAB",
+    sign_tx: "Sign transaction\nto send CRAB",
+    sign_blind_tx: "Accept risk and sign transaction\nto send CRAB",
+    review_batch: "Review batch of\ntransactions",
+    sign_batch: "Sign batch of\ntransactions",
+
+    amount: "Amount",
+    fees: "Fees",
+    token: "Token",
+    destination: "Destination",
+    memo: "Memo",
+    nonce: "Nonce",
+    path: "Path",
+    tx_hash: "Transaction hash",
+    from: "From",
+    transactions: "Transactions",
+    total: "Total",
+
+    verified: "(verified)",
+    unverified: "(unverified)",
+    account: "Account",
+    non_ascii_memo: "Non-ASCII memo, in hex:",
+
+    blind_signing_disabled: "Transaction rejected.\nEnable blind signing\nin Settings to sign it.",
+    spending_limit_refused: "Transaction rejected.\nSpending limit exceeded.",
+
+    continue_anyway: "Continue anyway",
+    reject_tx: "Reject transaction",
+    replay: "Possible replay",
+    replay_text: "Nonce {} is not greater than\nthe last signed nonce {}.",
+    spending_limit: "Spending limit exceeded",
+    spending_limit_text: "This transaction is over the\nspending limits you have set.",
+    self_transfer: "Transfer to yourself",
+    self_transfer_text: "The destination is the address\nof the signing account.",
+    zero_value: "Zero value",
+    zero_value_text: "This transaction does not\ntransfer anything.",
+    unverified_token: "Unverified token",
+    unverified_token_text: "The ticker and decimals of this\ntoken cannot be verified.",
+    high_value: "High value",
+    high_value_text: "The amount is above the high\nvalue threshold you have set.",
+
+    verify_address: "Verify CRAB address",
+
+    review_contact: "Review address\nto save",
+    save_contact: "Save address",
+    review_contact_removal: "Review address\nto remove",
+    remove_contact: "Remove address",
+    label: "Label",
+    address: "Address",
+
+    review_policy: "Review spending limits",
+    set_policy: "Set spending limits",
+    max_per_tx: "Max per transaction",
+    cumulative_limit: "Cumulative limit",
+    cumulative_window: "over the last {}\ntransactions",
+    over_limit: "Over limit",
+    high_value_above: "High value above",
+    no_limit: "None",
+    warn: "Warn",
+    refuse: "Refuse",
+
+    settings: [
+        ["Display Memo", "Allow display of transaction memo."],
+        [
+            "Blind signing",
+            "Allow signing of transactions that cannot be decoded.",
+        ],
+        [
+            "Expert mode",
+            "Display nonce, path and hash of transactions.",
+        ],
+        [
+            "Replay protection",
+            "Warn on reused nonces. Disable to reset known nonces.",
+        ],
+        [
+            "Spending limits",
+            "Enforce the spending limits set from the host.",
+        ],
+        [
+            "Address book",
+            "Show labels of saved addresses. Disable to erase them.",
+        ],
+    ],
+};
+
+/// French strings.
+pub const FR: Strings = Strings {
+    review_tx: "Verifier la transaction\npour envoyer CRAB",
+    sign_tx: "Signer la transaction\npour envoyer CRAB",
+    sign_blind_tx: "Accepter le risque et signer\nla transaction\npour envoyer CRAB",
+    review_batch: "Verifier le lot de\ntransactions",
+    sign_batch: "Signer le lot de\ntransactions",
+
+    amount: "Montant",
+    fees: "Frais",
+    token: "Jeton",
+    destination: "Destination",
+    memo: "Memo",
+    nonce: "Nonce",
+    path: "Chemin",
+    tx_hash: "Hash de la transaction",
+    from: "Depuis",
+    transactions: "Transactions",
+    total: "Total",
+
+    verified: "(verifie)",
+    unverified: "(non verifie)",
+    account: "Compte",
+    non_ascii_memo: "Memo non ASCII (hexa) :",
+
+    blind_signing_disabled:
+        "Transaction rejetee.\nActivez la signature aveugle\ndans les Parametres.",
+    spending_limit_refused: "Transaction rejetee.\nLimite de depenses depassee.",
+
+    continue_anyway: "Continuer quand meme",
+    reject_tx: "Rejeter la transaction",
+    replay: "Rejeu possible",
+    replay_text: "Le nonce {} n'est pas superieur\nau dernier nonce signe {}.",
+    spending_limit: "Limite depassee",
+    spending_limit_text: "Cette transaction depasse les\nlimites de depenses definies.",
+    self_transfer: "Transfert vers vous-meme",
+    self_transfer_text: "La destination est l'adresse\ndu compte signataire.",
+    zero_value: "Valeur nulle",
+    zero_value_text: "Cette transaction ne\ntransfere rien.",
+    unverified_token: "Jeton non verifie",
+    unverified_token_text: "Le symbole et les decimales de\nce jeton ne sont pas verifies.",
+    high_value: "Montant eleve",
+    high_value_text: "Le montant depasse le seuil\nde montant eleve defini.",
+
+    verify_address: "Verifier l'adresse CRAB",
+
+    review_contact: "Verifier l'adresse\na enregistrer",
+    save_contact: "Enregistrer l'adresse",
+    review_contact_removal: "Verifier l'adresse\na supprimer",
+    remove_contact: "Supprimer l'adresse",
+    label: "Libelle",
+    address: "Adresse",
+
+    review_policy: "Verifier les limites\nde depenses",
+    set_policy: "Definir les limites\nde depenses",
+    max_per_tx: "Max. par transaction",
+    cumulative_limit: "Limite cumulee",
+    cumulative_window: "sur les {} dernieres\ntransactions",
+    over_limit: "Au-dela des limites",
+    high_value_above: "Montant eleve au-dela de",
+    no_limit: "Aucune",
+    warn: "Avertir",
+    refuse: "Refuser",
+
+    settings: [
+        [
+            "Afficher le memo",
+            "Autoriser l'affichage du memo des transactions.",
+        ],
+        [
+            "Signature aveugle",
+            "Autoriser la signature des transactions non decodables.",
+        ],
+        [
+            "Mode expert",
+            "Afficher le nonce, le chemin et le hash des transactions.",
+        ],
+        [
+            "Protection anti-rejeu",
+            "Avertir des nonces reutilises. Desactiver pour oublier les nonces connus.",
+        ],
+        [
+            "Limites de depenses",
+            "Appliquer les limites de depenses definies par l'hote.",
+        ],
+        [
+            "Carnet d'adresses",
+            "Afficher les libelles des adresses enregistrees. Desactiver pour les effacer.",
+        ],
+    ],
+};
+
+/// Strings of the language selected at build time.
+#[cfg(not(feature = "lang_fr"))]
+pub const STRINGS: &Strings = &EN;
+/// Strings of the language selected at build time.
+#[cfg(feature = "lang_fr")]
+pub const STRINGS: &Strings = &FR;
+
+const _: () = check(&EN);
+const _: () = check(&FR);
+
+/// Returns true if `text` holds at most `max_lines` lines of at most `max_line_len` characters.
+const fn fits(text: &str, max_lines: usize, max_line_len: usize) -> bool {
+    let bytes = text.as_bytes();
+    let mut lines = 1;
+    let mut line_len = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\n' {
+            lines += 1;
+            line_len = 0;
+        } else {
+            line_len += 1;
+            if line_len > max_line_len {
+                return false;
+            }
+        }
+        i += 1;
+    }
+    !text.is_empty() && lines <= max_lines
+}
+
+/// Returns true if `text` only holds printable ASCII characters and line breaks, which are the
+/// only characters the NBGL fonts can display.
+const fn is_displayable(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\n' && (bytes[i] < 0x20 || bytes[i] > 0x7E) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Fails the build if a string of `strings` is over its length budget, or holds a character the
+/// NBGL fonts cannot display.
+pub const fn check(strings: &Strings) {
+    let texts = [
+        strings.review_tx,
+        strings.sign_tx,
+        strings.sign_blind_tx,
+        strings.review_batch,
+        strings.sign_batch,
+        strings.blind_signing_disabled,
+        strings.spending_limit_refused,
+        strings.replay_text,
+        strings.spending_limit_text,
+        strings.self_transfer_text,
+        strings.zero_value_text,
+        strings.unverified_token_text,
+        strings.high_value_text,
+        strings.verify_address,
+        strings.review_contact,
+        strings.save_contact,
+        strings.review_contact_removal,
+        strings.remove_contact,
+        strings.review_policy,
+        strings.set_policy,
+        strings.cumulative_window,
+    ];
+    let mut i = 0;
+    while i < texts.len() {
+        assert!(is_displayable(texts[i]), "text not printable ASCII");
+        assert!(fits(texts[i], MAX_LINES, MAX_LINE_LEN), "text too long");
+        i += 1;
+    }
+
+    let labels = [
+        strings.amount,
+        strings.fees,
+        strings.token,
+        strings.destination,
+        strings.memo,
+        strings.nonce,
+        strings.path,
+        strings.tx_hash,
+        strings.from,
+        strings.transactions,
+        strings.total,
+        strings.verified,
+        strings.unverified,
+        strings.account,
+        strings.non_ascii_memo,
+        strings.continue_anyway,
+        strings.reject_tx,
+        strings.replay,
+        strings.spending_limit,
+        strings.self_transfer,
+        strings.zero_value,
+        strings.unverified_token,
+        strings.high_value,
+        strings.label,
+        strings.address,
+        strings.max_per_tx,
+        strings.cumulative_limit,
+        strings.over_limit,
+        strings.high_value_above,
+        strings.no_limit,
+        strings.warn,
+        strings.refuse,
+    ];
+    let mut i = 0;
+    while i < labels.len() {
+        assert!(is_displayable(labels[i]), "label not printable ASCII");
+        assert!(fits(labels[i], 1, MAX_LABEL_LEN), "label too long");
+        i += 1;
+    }
+
+    let mut i = 0;
+    while i < SETTINGS_COUNT {
+        let [name, description] = strings.settings[i];
+        assert!(
+            is_displayable(name) && is_displayable(description),
+            "setting not printable ASCII"
+        );
+        assert!(fits(name, 1, MAX_SETTING_NAME_LEN), "setting name too long");
+        assert!(
+            fits(description, 1, MAX_SETTING_DESCRIPTION_LEN),
+            "setting description too long"
+        );
+        i += 1;
+    }
+}
+
+/// Replaces the `{}` placeholders of `template` with `values`, in order.
+pub fn fill(template: &str, values: &[&dyn core::fmt::Display]) -> alloc::string::String {
+    use core::fmt::Write;
+
+    let mut text = alloc::string::String::with_capacity(template.len());
+    let mut values = values.iter();
+    for (i, part) in template.split("{}").enumerate() {
+        if i > 0 {
+            if let Some(value) = values.next() {
+                let _ = write!(text, "{}", value);
+            }
+        }
+        text.push_str(part);
+    }
+    text
+}
@@ -40,6 +40,24 @@
 //! 1. **CHECK_ADDRESS**: Verify the destination address belongs to this device
 //! 2. **GET_PRINTABLE_AMOUNT**: Format amounts for display (fees, totals)
 //! 3. **SIGN_TRANSACTION**: Sign the transaction (UI bypass - already validated by Exchange)
+//!
+//! ## Exchange Sub-Flows
+//!
+//! The Exchange app uses the same library calls for its three sub-flows, and does not tell the
+//! coin app which one is running:
+//!
+//! - **SWAP**: Exchange this coin for another one. CHECK_ADDRESS is called on the refund address
+//!   (and on the payout address when this coin is the one received). The payin transaction is
+//!   sent to the swap provider.
+//! - **SELL**: Sell this coin for fiat. The payin transaction is sent to the account of the
+//!   provider.
+//! - **FUND**: Fund an account held by a provider (e.g. a card), from this coin. The payin
+//!   transaction is sent to the provider account.
+//!
+//! In SELL and FUND, no address of this device is involved: CHECK_ADDRESS is not called, and the
+//! destination of the transaction is an account of the provider, not an address derived by this
+//! device. [`check_swap_params`] therefore only validates the destination against the one
+//! signed by the provider, which holds for every sub-flow.
 
 use arrayvec::ArrayString;
 use core::fmt::Write;
@@ -57,10 +75,49 @@ use ledger_device_sdk::{
     log,
 };
 
-use crate::handlers::sign_tx::Tx;
-use crate::utils::get_address_hash_from_pubkey;
+use crate::spending_policy::{LimitCheck, SpendingPolicy};
+use crate::token::{TokenRegistry, MAX_TICKER_LEN};
+use crate::tx::Tx;
+use crate::utils::{escape_non_printable, get_address_hash_from_pubkey, Bip32Path};
 use alloc::{format, string::ToString};
 
+/// Ticker of the native coin.
+const CRAB_TICKER: &str = "CRAB";
+
+/// Maximum number of components of a derivation path.
+const MAX_PATH_LEN: usize = 10;
+
+/// Derivation path prefix of the accounts of this app (m/44'/1').
+const PATH_PREFIX: [u32; 2] = [0x8000_002C, 0x8000_0001];
+
+/// Returns the swap error of a transaction that cannot be parsed.
+///
+/// Called instead of [`check_swap_params`] when the transaction received in swap mode cannot be
+/// deserialized.
+pub fn tx_parsing_error() -> SwapError<SwapAppErrorCode> {
+    log::error!("Swap tx parsing failed");
+    SwapError::with_message(
+        SwapErrorCommonCode::ErrorGeneric,
+        SwapAppErrorCode::TxParsingFail,
+        "Failed to parse the transaction".to_string(),
+    )
+}
+
+/// Returns the swap error of a malformed coin configuration.
+fn coin_config_error() -> SwapError<SwapAppErrorCode> {
+    log::error!("Malformed coin configuration");
+    SwapError::with_message(
+        SwapErrorCommonCode::ErrorGeneric,
+        SwapAppErrorCode::CoinConfigDecodeFail,
+        "Failed to parse the coin configuration".to_string(),
+    )
+}
+
+/// Reads an amount from an Exchange buffer (right aligned big-endian, 16 bytes).
+fn read_amount(buf: &[u8; 16]) -> u128 {
+    u128::from_be_bytes(*buf)
+}
+
 /// Application-specific swap error codes.
 ///
 /// This enum defines the lower byte of the 2-byte error code used in swap error responses.
@@ -80,28 +137,16 @@ use alloc::{format, string::ToString};
 /// ```rust,ignore
 /// use ledger_device_sdk::libcall::swap::SwapErrorCommonCode;
 ///
-/// // Example: Amount validation failed, no app-specific refinement needed
+/// // Example: Amount validation failed, refined by the app-specific code
 /// comm.append(&[
 ///     SwapErrorCommonCode::ErrorWrongAmount as u8,
-///     SwapAppErrorCode::Default as u8
+///     SwapAppErrorCode::AmountMismatch as u8
 /// ]);
 /// comm.append(b"Amount mismatch: tx=1000, swap=2000");
 /// ```
 ///
-/// # Template Note
-///
-/// This is a template/placeholder enum. In a production application, you may want to add
-/// specific error codes to provide more granular error information. For example:
-///
-/// ```rust,ignore
-/// pub enum SwapAppErrorCode {
-///     Default = 0x00,
-///     AmountOverflow = 0x01,
-///     AmountUnderflow = 0x02,
-///     InvalidAddressChecksum = 0x03,
-///     // ... add your application-specific codes
-/// }
-/// ```
+/// Every failure of the swap validation has its own code, so that the Exchange can tell the
+/// user what went wrong.
 ///
 /// Refer to the C SDK's `swap_error_code_helpers.h` for the specification.
 #[repr(u8)]
@@ -112,9 +157,35 @@ pub enum SwapAppErrorCode {
     /// Use this when the common error code from `SwapErrorCommonCode` is sufficient
     /// and no additional application-specific context is needed.
     Default = 0x00,
-    /// Other error codes, don't hesitate to add your own for more granularity.
+    /// The swap amount does not fit the u64 transaction value.
     AmountCastFail = 0x01,
+    /// The swap destination is not a hex encoded address.
     DestinationDecodeFail = 0x02,
+    /// The transaction value is over the on-device spending limits.
+    SpendingLimitExceeded = 0x03,
+    /// The swap destination extra id is not valid UTF-8.
+    MemoDecodeFail = 0x04,
+    /// The transaction memo differs from the swap destination extra id.
+    MemoMismatch = 0x05,
+    /// The transaction could not be parsed.
+    TxParsingFail = 0x06,
+    /// The transaction does not transfer the kind of coin of the swap: a token transfer when the
+    /// native coin is swapped or the reverse, or a transfer of another native coin.
+    WrongTxType = 0x07,
+    /// The swap fees differ from the transaction fees.
+    FeeMismatch = 0x08,
+    /// The signing path is not a valid account path of this app.
+    PathRejected = 0x09,
+    // 0x0A is not used, so that the codes known by the Exchange keep their meaning.
+    /// The transaction value differs from the swap amount.
+    AmountMismatch = 0x0B,
+    /// The transaction destination differs from the swap destination.
+    DestinationMismatch = 0x0C,
+    /// The coin configuration of the swap is malformed.
+    CoinConfigDecodeFail = 0x0D,
+    /// The transferred token has no verified descriptor, or its ticker or decimals differ from
+    /// the coin configuration of the swap.
+    TokenMismatch = 0x0E,
 }
 
 impl SwapAppErrorCodeTrait for SwapAppErrorCode {
@@ -128,71 +199,175 @@ impl SwapAppErrorCodeTrait for SwapAppErrorCode {
 /// This function performs a strict validation of the transaction to be signed
 /// against the reference transaction parameters provided by the Exchange app.
 /// It checks that:
-/// 1. The transaction type matches the expected one (Only one type of Tx implemented so auto true).
-/// 2. The transaction amount matches the swap amount exactly.
-/// 3. The transaction fees matches the swap fees exactly. (fees are not implemented so auto true).
-/// 4. The destination address matches the swap destination address exactly.
+/// 1. The signing path is an account path of this app.
+/// 2. The transaction transfers the coin of the swap. For a token, described by the coin
+///    configuration of the Exchange, the contract of the transaction must have a verified
+///    descriptor (provided with PROVIDE_TOKEN_INFO during the swap session) with the same ticker
+///    and decimals.
+/// 3. The transaction amount matches the swap amount exactly.
+/// 4. The transaction fees matches the swap fees exactly.
+/// 5. The destination address matches the swap destination address exactly. This is the
+///    provider address for a SWAP, or the provider account for a SELL or a FUND.
+/// 6. The memo matches the destination extra id (memo / destination tag) exactly.
+/// 7. The transaction amount is within the on-device spending limits, if enforced. Limits are
+///    expressed in the native coin, token transfers are not checked.
 ///
 /// # Errors
 ///
 /// Returns error if:
-/// - Amount parsing fails (AmountCastFail)
-/// - Amount doesn't match between tx and swap params (ErrorWrongAmount)
-/// - Destination address has invalid UTF-8 (DestinationDecodeFail)
+/// - The path is too long or outside of m/44'/1' (PathRejected)
+/// - The coin configuration is malformed (CoinConfigDecodeFail)
+/// - The token address of the transaction is not valid hex (TxParsingFail)
+/// - The transaction is a native transfer of another coin, or a token transfer when the native
+///   coin is swapped, or the reverse (WrongTxType)
+/// - The token has no verified descriptor, or it does not match the coin configuration
+///   (TokenMismatch)
+/// - Amount doesn't fit the u64 transaction value (AmountCastFail)
+/// - Amount doesn't match between tx and swap params (AmountMismatch)
+/// - Fees don't match between tx and swap params (FeeMismatch)
+/// - Destination address has an invalid length or invalid UTF-8 (DestinationDecodeFail)
 /// - Destination address hex decode fails (DestinationDecodeFail)
-/// - Destination address doesn't match (ErrorWrongDestination)
+/// - Destination address doesn't match (DestinationMismatch)
+/// - Destination extra id has an invalid length or invalid UTF-8 (MemoDecodeFail)
+/// - Memo doesn't match the destination extra id (MemoMismatch)
+/// - Amount is over the spending limits (SpendingLimitExceeded)
 pub fn check_swap_params(
     params: &CreateTxParams,
+    path: &Bip32Path,
     tx: &Tx,
+    tokens: &TokenRegistry,
 ) -> Result<(), SwapError<SwapAppErrorCode>> {
     log::debug!("Swap mode detected\n");
 
+    // Validate path
+    let path = path.as_ref();
+    if path.len() > MAX_PATH_LEN || !path.starts_with(&PATH_PREFIX) {
+        log::error!("Swap path rejected");
+        return Err(SwapError::with_message(
+            SwapErrorCommonCode::ErrorGeneric,
+            SwapAppErrorCode::PathRejected,
+            "Signing path is not an account path of this app".to_string(),
+        ));
+    }
+
+    // Validate the transferred coin against the coin configuration of the Exchange
+    let coin_config = params
+        .coin_config
+        .get(..params.coin_config_len)
+        .ok_or_else(coin_config_error)?;
+    let config = CoinConfig::parse(coin_config).ok_or_else(coin_config_error)?;
+    let token_address = tx.token_address().map_err(|_| {
+        log::error!("Swap tx token address decode fail");
+        SwapError::with_message(
+            SwapErrorCommonCode::ErrorGeneric,
+            SwapAppErrorCode::TxParsingFail,
+            "Failed to decode token address hex".to_string(),
+        )
+    })?;
+
+    // An empty configuration is the one of the native coin
+    match (coin_config.is_empty(), token_address) {
+        (true, None) if tx.coin == CRAB_TICKER => {}
+        (false, Some(address)) => {
+            let matches = tokens.find(&address).is_some_and(|token| {
+                token.ticker() == config.ticker.as_str() && token.decimals() == config.decimals
+            });
+            if !matches {
+                log::error!("Swap tx token mismatch");
+                return Err(SwapError::with_message(
+                    SwapErrorCommonCode::ErrorWrongMethod,
+                    SwapAppErrorCode::TokenMismatch,
+                    format!(
+                        "Token {} is not a verified {} token",
+                        hex::encode(address),
+                        config.ticker
+                    ),
+                ));
+            }
+        }
+        _ => {
+            log::error!("Swap tx does not transfer the swapped coin");
+            return Err(SwapError::with_message(
+                SwapErrorCommonCode::ErrorWrongMethod,
+                SwapAppErrorCode::WrongTxType,
+                format!("Transaction is not a {} transfer", config.ticker),
+            ));
+        }
+    }
+
     // Validate amount
-    // Parse amount (u64 from big-endian bytes, right aligned in 16-byte buffer)
+    // Parse amount (u128 from big-endian bytes, right aligned in 16-byte buffer)
     // Amount is stored in AMOUNT_BUF_SIZE (16 bytes) buffer, right-aligned big-endian
-    let start = params.amount.len() - 8;
-    let amount_bytes: [u8; 8] = params.amount[start..].try_into().map_err(|_| {
-        SwapError::without_message(
+    let swap_amount = read_amount(&params.amount);
+
+    // The transaction model only carries u64 values (see `Tx::value`): a wider swap amount can
+    // never be matched, and must not be compared after truncation.
+    if swap_amount > u64::MAX as u128 {
+        log::error!("Swap amount does not fit the transaction value\n");
+        debug_u128("Swap: ", swap_amount);
+        return Err(SwapError::with_message(
             SwapErrorCommonCode::ErrorWrongAmount,
             SwapAppErrorCode::AmountCastFail,
-        )
-    })?;
-    let swap_amount = u64::from_be_bytes(amount_bytes);
+            format!(
+                "Amount swap {} exceeds the transaction value range",
+                swap_amount
+            ),
+        ));
+    }
 
     //  --8<-- [start:SwapError_example]
-    if tx.value != swap_amount {
+    if tx.value as u128 != swap_amount {
         log::debug!("Swap amount mismatch\n");
-        debug_u64("Tx: ", tx.value);
-        debug_u64("Swap: ", swap_amount);
+        debug_u128("Tx: ", tx.value as u128);
+        debug_u128("Swap: ", swap_amount);
         // Error detected, we return the error with detailed message in common SDK defined format
         return Err(SwapError::with_message(
             SwapErrorCommonCode::ErrorWrongAmount,
-            SwapAppErrorCode::Default,
+            SwapAppErrorCode::AmountMismatch,
             format!("Amount tx {} != swap {}", tx.value, swap_amount),
         ));
     }
     //  --8<-- [end:SwapError_example]
 
-    // Validate destination
-    let dest_str =
-        core::str::from_utf8(&params.dest_address[..params.dest_address_len]).map_err(|_| {
+    // Validate fees
+    let swap_fees = read_amount(&params.fee_amount);
+    if tx.fee as u128 != swap_fees {
+        log::error!("Swap fees mismatch");
+        debug_u128("Tx fees: ", tx.fee as u128);
+        debug_u128("Swap fees: ", swap_fees);
+        return Err(SwapError::with_message(
+            SwapErrorCommonCode::ErrorWrongFees,
+            SwapAppErrorCode::FeeMismatch,
+            format!("Fees tx {} != swap {}", tx.fee, swap_fees),
+        ));
+    }
+
+    // Validate destination. In a SELL or a FUND, this is an account of the provider, which is
+    // not checked by CHECK_ADDRESS beforehand: it is decoded as leniently as the refund address,
+    // whatever its prefix and case.
+    let dest_str = params
+        .dest_address
+        .get(..params.dest_address_len)
+        .and_then(|dest| core::str::from_utf8(dest).ok())
+        .ok_or_else(|| {
             SwapError::with_message(
                 SwapErrorCommonCode::ErrorWrongDestination,
                 SwapAppErrorCode::DestinationDecodeFail,
                 "Failed to read destination hex".to_string(),
             )
         })?;
-    let dest_hex = dest_str.strip_prefix("0x").unwrap_or(dest_str);
 
-    let mut swap_dest = [0u8; 20];
-    if hex::decode_to_slice(dest_hex, &mut swap_dest).is_err() {
+    let Some(swap_dest) = decode_address(dest_str.as_bytes()) else {
         log::error!("Swap dest hex decode fail");
         return Err(SwapError::with_message(
             SwapErrorCommonCode::ErrorWrongDestination,
             SwapAppErrorCode::DestinationDecodeFail,
-            format!("Failed to decode destination hex: {}", dest_hex),
+            format!(
+                "Failed to decode destination hex: {}",
+                escape_non_printable(dest_str)
+            ),
         ));
-    }
+    };
 
     if tx.to != swap_dest {
         log::error!("Swap destination mismatch");
@@ -203,18 +378,57 @@ pub fn check_swap_params(
         let swap_hex = hex::encode(swap_dest);
         return Err(SwapError::with_message(
             SwapErrorCommonCode::ErrorWrongDestination,
-            SwapAppErrorCode::Default,
+            SwapAppErrorCode::DestinationMismatch,
             format!("Destination mismatch: tx {} != swap {}", tx_hex, swap_hex),
         ));
     }
 
+    // Validate memo. The Exchange provides the extra id required by the provider on the payin
+    // transaction (empty if none): the transaction memo must match it exactly.
+    let swap_memo = params
+        .dest_address_extra_id
+        .get(..params.dest_address_extra_id_len)
+        .and_then(|extra_id| core::str::from_utf8(extra_id).ok())
+        .ok_or_else(|| {
+            SwapError::with_message(
+                SwapErrorCommonCode::ErrorWrongDestination,
+                SwapAppErrorCode::MemoDecodeFail,
+                "Failed to read destination extra id".to_string(),
+            )
+        })?;
+
+    if tx.memo != swap_memo {
+        // Both memos are untrusted text, escape them before logging or reporting them
+        let tx_memo = escape_non_printable(tx.memo);
+        let swap_memo = escape_non_printable(swap_memo);
+        log::error!("Swap memo mismatch");
+        log::error!("Tx: {}", tx_memo);
+        log::error!("Swap: {}", swap_memo);
+        return Err(SwapError::with_message(
+            SwapErrorCommonCode::ErrorWrongDestination,
+            SwapAppErrorCode::MemoMismatch,
+            format!("Memo mismatch: tx '{}' != swap '{}'", tx_memo, swap_memo),
+        ));
+    }
+
+    // Validate spending limits. There is no review in swap mode, so a transaction over the
+    // limits is refused whatever the policy action.
+    if token_address.is_none() && SpendingPolicy.check(tx.value) != LimitCheck::Allowed {
+        log::error!("Swap amount over the spending limits");
+        return Err(SwapError::with_message(
+            SwapErrorCommonCode::ErrorWrongAmount,
+            SwapAppErrorCode::SpendingLimitExceeded,
+            format!("Amount {} over the spending limits", tx.value),
+        ));
+    }
+
     log::debug!("Swap validation success, bypassing UI");
     Ok(())
 }
 //  --8<-- [end:check_swap_params]
 
-/// Helper function to print u64 for debugging.
-pub fn debug_u64(label: &str, val: u64) {
+/// Helper function to print u128 for debugging.
+pub fn debug_u128(label: &str, val: u128) {
     let mut buf = ArrayString::<64>::new();
     let _ = writeln!(&mut buf, "{}{}", label, val);
     log::debug!("{}", buf.as_str());
@@ -239,7 +453,8 @@ pub fn debug_hex(label: &str, data: &[u8]) {
 ///
 /// * `arg0` - Parameter passed by `os_lib_call` containing command and data pointers
 ///
-/// The Exchange app calls this function with different commands during a swap:
+/// The Exchange app calls this function with different commands during a SWAP, SELL or FUND
+/// (see the module documentation for the differences between them):
 /// - `SwapCheckAddress`: Validate that an address belongs to this device
 /// - `SwapGetPrintableAmount`: Format amounts/fees for display
 /// - `SwapSignTransaction`: Sign the final transaction
@@ -284,21 +499,23 @@ pub fn swap_main(arg0: u32) {
 // --8<-- [start:check_address]
 /// Verify that a given address belongs to this device.
 ///
-/// The Exchange app calls this to ensure the user owns the destination address
-/// before proceeding with the swap. This prevents sending funds to wrong addresses.
+/// The Exchange app calls this to ensure the user owns an address before proceeding with the
+/// swap: the payout address, when this coin is the one received, and the refund address, when
+/// this coin is the one sent. This prevents sending funds to wrong addresses.
 ///
 /// # Flow
 ///
 /// 1. Parse BIP32 derivation path from params
 /// 2. Derive public key from the path
 /// 3. Compute address from public key (Keccak256 hash)
-/// 4. Compare with reference address from Exchange
+/// 4. Decode the reference address from Exchange and compare the address bytes
 ///
 /// # Important Notes
 ///
 /// - **No heap allocation**: Uses stack arrays only (BSS memory is shared with Exchange)
-/// - **Hex string comparison**: Exchange sends address as hex string via C API,
-///   so we convert our computed address to hex for comparison
+/// - **Byte comparison**: Exchange sends address as hex string via C API. It is decoded
+///   before the comparison, so that an optional `0x` prefix and the case of the hex digits
+///   (e.g. EIP-55 checksum case) do not matter
 /// - **Address format**: This app uses Ethereum-style addresses (last 20 bytes of
 ///   Keccak256 hash of pubkey). Adapt this for your blockchain's address format.
 ///
@@ -310,30 +527,40 @@ pub fn swap_main(arg0: u32) {
 ///
 /// * `1` if addresses match (valid)
 /// * `0` if addresses don't match or error occurred
-fn check_address(params: &CheckAddressParams) -> i32 {
+pub fn check_address(params: &CheckAddressParams) -> i32 {
     // Parse BIP32 derivation path
     // Note: params.dpath_len is the NUMBER of u32 path components (e.g., 5 for m/44'/1'/0'/0/0),
     // not the byte length. Each component is 4 bytes (big-endian u32).
-    let path_bytes = &params.dpath[..params.dpath_len * 4];
-
-    // Use stack-allocated array (no heap!) to store parsed path
-    let mut path: [u32; 10] = [0; 10]; // Max 10 derivation levels
-
-    if params.dpath_len > 10 {
+    if params.dpath_len > MAX_PATH_LEN {
         log::error!("Path too long\n");
         return 0;
     }
+    let Some(path_bytes) = params.dpath.get(..params.dpath_len * 4) else {
+        log::error!("Path length exceeds buffer\n");
+        return 0;
+    };
+
+    // Use stack-allocated array (no heap!) to store parsed path
+    let mut path: [u32; MAX_PATH_LEN] = [0; MAX_PATH_LEN];
 
     // Convert big-endian bytes to u32 path components
-    for i in 0..params.dpath_len {
-        path[i] = u32::from_be_bytes([
-            path_bytes[i * 4],
-            path_bytes[i * 4 + 1],
-            path_bytes[i * 4 + 2],
-            path_bytes[i * 4 + 3],
-        ]);
+    for (component, bytes) in path.iter_mut().zip(path_bytes.chunks_exact(4)) {
+        *component = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
     }
 
+    // Exchange sends address bytes, but SDK's read_c_string() interprets them as
+    // a hex string. This is a quirk of the C API - the Exchange sends binary address
+    // bytes, but they're read as ASCII characters.
+    // Example: byte 0x04 becomes ASCII '0' (0x30) and '4' (0x34) = "04" in the string
+    let Some(ref_address) = params
+        .ref_address
+        .get(..params.ref_address_len)
+        .and_then(decode_address)
+    else {
+        log::error!("Reference address is not a hex encoded address\n");
+        return 0;
+    };
+
     // Derive public key from path using the same logic as get_public_key handler
     let (k, _) = Secp256k1::derive_from(&path[..params.dpath_len]);
     let pubkey = match k.public_key() {
@@ -347,37 +574,94 @@ fn check_address(params: &CheckAddressParams) -> i32 {
     // Compute address: Keccak256 hash of pubkey (excluding first byte 0x04)
     let address_hash = get_address_hash_from_pubkey(&pubkey);
     // Take last 20 bytes as address (Ethereum-style)
-    let address = &address_hash[address_hash.len() - 20..];
+    let address = &address_hash[address_hash.len() - ADDRESS_LEN..];
 
-    // Exchange sends address bytes, but SDK's read_c_string() interprets them as
-    // a hex string. This is a quirk of the C API - the Exchange sends binary address
-    // bytes, but they're read as ASCII characters.
-    // Example: byte 0x04 becomes ASCII '0' (0x30) and '4' (0x34) = "04" in the string
-    let ref_hex = match core::str::from_utf8(&params.ref_address[..params.ref_address_len]) {
-        Ok(s) => s,
-        Err(_) => return 0,
-    };
-
-    // Convert our derived address to hex string for comparison
-    // Using ArrayString (stack-allocated) to avoid heap allocation
-    let mut our_hex = ArrayString::<40>::new(); // 20 bytes * 2 hex chars
-    for b in address {
-        let _ = write!(&mut our_hex, "{:02x}", b);
-    }
-
-    // Compare hex strings
-    if our_hex.as_str() == ref_hex {
+    // Compare address bytes
+    if address == ref_address {
         log::debug!("Check address successful, derived and received addresses match\n");
         1 // Success
     } else {
         log::debug!("Derived and received addresses do NOT match\n");
         log::debug!("Derived address: {:x?}", address);
-        log::debug!("Reference (hex): {}", ref_hex);
+        log::debug!("Reference address: {:x?}", ref_address);
         0 // Failure
     }
 }
+
+/// Length of an address, in bytes.
+const ADDRESS_LEN: usize = 20;
+
+/// Decodes a hex encoded address, with or without `0x` prefix, in any case.
+///
+/// Stack-only, so that it can be used under the BSS memory restrictions.
+fn decode_address(hex_address: &[u8]) -> Option<[u8; ADDRESS_LEN]> {
+    let digits = match hex_address {
+        [b'0', b'x' | b'X', digits @ ..] => digits,
+        digits => digits,
+    };
+    let mut address = [0u8; ADDRESS_LEN];
+    hex::decode_to_slice(digits, &mut address).ok()?;
+    Some(address)
+}
 // --8<-- [end:check_address]
 
+/// Ticker and decimals of the coin or token of a swap.
+///
+/// Stack-allocated, so that it can be used under the BSS memory restrictions.
+struct CoinConfig {
+    ticker: ArrayString<MAX_TICKER_LEN>,
+    decimals: u8,
+}
+
+impl CoinConfig {
+    /// Configuration of the native coin.
+    ///
+    /// CRAB uses 9 decimals (similar to SUI, which also uses 9 decimals)
+    fn native() -> CoinConfig {
+        const CRAB_DECIMALS: u8 = 9;
+
+        let mut ticker = ArrayString::new();
+        let _ = ticker.try_push_str(CRAB_TICKER);
+        CoinConfig {
+            ticker,
+            decimals: CRAB_DECIMALS,
+        }
+    }
+
+    /// Parses the coin configuration sent by the Exchange app.
+    ///
+    /// # Format
+    ///
+    /// - Empty for the native coin
+    /// - Otherwise, for a token:
+    ///   - 1 byte: Ticker length (1 to [`MAX_TICKER_LEN`])
+    ///   - Ticker bytes, printable ASCII
+    ///   - 1 byte: Decimals
+    ///
+    /// Returns `None` if the configuration is malformed.
+    fn parse(config: &[u8]) -> Option<CoinConfig> {
+        let Some((&ticker_len, rest)) = config.split_first() else {
+            return Some(CoinConfig::native());
+        };
+        let ticker_len = ticker_len as usize;
+        if ticker_len == 0 || ticker_len > MAX_TICKER_LEN || rest.len() != ticker_len + 1 {
+            return None;
+        }
+
+        let ticker_str = core::str::from_utf8(&rest[..ticker_len]).ok()?;
+        if !ticker_str.bytes().all(|c| c.is_ascii_graphic()) {
+            return None;
+        }
+        let mut ticker = ArrayString::new();
+        ticker.try_push_str(ticker_str).ok()?;
+
+        Some(CoinConfig {
+            ticker,
+            decimals: rest[ticker_len],
+        })
+    }
+}
+
 // --8<-- [start:get_printable_amount]
 /// Format an amount for display in the Exchange app UI.
 ///
@@ -397,47 +681,69 @@ fn check_address(params: &CheckAddressParams) -> i32 {
 /// * `params` - Contains:
 ///   - `amount`: Big-endian encoded amount bytes (right-aligned in 16-byte buffer)
 ///   - `amount_len`: Actual number of significant bytes
-///   - `coin_config`: Coin configuration (unused - hardcoded to CRAB in this template)
-///   - `is_fee`: Whether this is a fee amount
+///   - `coin_config`: Coin configuration, see [`CoinConfig::parse`]. Empty for the native coin
+///   - `is_fee`: Whether this is a fee amount. Fees are always paid in the native coin
 ///
 /// # Returns
 ///
-/// Stack-allocated string formatted as "CRAB {value}" (e.g., "CRAB 1.5")
+/// Stack-allocated string formatted as "{ticker} {value}" (e.g., "CRAB 1.5"), or an empty
+/// string if `amount_len` or `coin_config` is malformed, or if the formatted amount is too
+/// long.
 ///
 /// # Memory Safety
 ///
 /// Uses `ArrayString` (stack-allocated) to avoid heap allocation, as this function
 /// runs under BSS memory restrictions.
-///
-/// # Production Notes
-///
-/// For a production app, you should:
-/// - Parse `coin_config` to extract ticker and decimals dynamically
-/// - Handle different coin types
-/// - Support both u64 and u128 amounts
-fn get_printable_amount(params: &PrintableAmountParams) -> ArrayString<40> {
+pub fn get_printable_amount(params: &PrintableAmountParams) -> ArrayString<40> {
     // Convert amount from 16-byte buffer to 32-byte buffer (uint256 format)
     // The amount is right-aligned in params.amount, we need to copy it to a
     // 32-byte buffer that's also right-aligned (big-endian)
+    let mut printable = ArrayString::<40>::new();
+
+    let Some(src_start) = params.amount.len().checked_sub(params.amount_len) else {
+        log::error!("Amount length exceeds buffer\n");
+        return printable;
+    };
     let mut amount_u256: [u8; 32] = [0; 32];
-    let src_start = params.amount.len() - params.amount_len;
     let dst_start = 32 - params.amount_len;
     amount_u256[dst_start..].copy_from_slice(&params.amount[src_start..]);
 
     log::debug!("Amount bytes (u256): ");
     debug_hex("", &amount_u256);
 
-    // CRAB uses 9 decimals (similar to SUI, which also uses 9 decimals)
-    // For production: parse decimals from params.coin_config
-    const CRAB_DECIMALS: usize = 9;
-    const CRAB_TICKER: &str = "CRAB";
+    // Fees are paid in the native coin, even when a token is swapped
+    let config = if params.is_fee {
+        CoinConfig::native()
+    } else {
+        match params
+            .coin_config
+            .get(..params.coin_config_len)
+            .and_then(CoinConfig::parse)
+        {
+            Some(config) => config,
+            None => {
+                log::error!("Malformed coin configuration\n");
+                return printable;
+            }
+        }
+    };
 
     // Use SDK helper to format amount with decimals
-    let amount_str = uint256_to_float(&amount_u256, CRAB_DECIMALS);
-
-    // Format as "CRAB {value}" using stack-allocated ArrayString
-    let mut printable = ArrayString::<40>::new();
-    let _ = write!(&mut printable, "{} {}", CRAB_TICKER, amount_str.as_str());
+    let amount_str = uint256_to_float(&amount_u256, config.decimals as usize);
+
+    // Format as "{ticker} {value}" using stack-allocated ArrayString
+    // An amount too long for the buffer is not truncated, it is reported as an empty string
+    if write!(
+        &mut printable,
+        "{} {}",
+        config.ticker.as_str(),
+        amount_str.as_str()
+    )
+    .is_err()
+    {
+        log::error!("Printable amount too long\n");
+        printable.clear();
+    }
 
     log::debug!("Formatted amount: {}", printable.as_str());
 
@@ -0,0 +1,151 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+//! Token registry
+//!
+//! A token descriptor gives the ticker and decimals of a token contract. It is signed by the
+//! trusted authority (see [`verify_trusted_signature`]), so transfers of this token can be
+//! reviewed with a verified ticker and a decimal-formatted amount.
+//!
+//! Descriptors are kept in RAM for the whole session, up to [`MAX_TOKENS`] of them.
+
+use crate::utils::{verify_trusted_signature, DescriptorType};
+use crate::AppSW;
+use alloc::vec::Vec;
+
+/// Maximum number of token descriptors kept in the registry.
+pub const MAX_TOKENS: usize = 8;
+/// Maximum length of a ticker, in bytes.
+pub const MAX_TICKER_LEN: usize = 10;
+/// Maximum number of decimals of a token.
+pub const MAX_DECIMALS: u8 = 30;
+
+/// Chain id expected in token descriptors, which depends on the network of the app variant.
+pub const CHAIN_ID: u64 = if cfg!(feature = "variant_testnet") {
+    2
+} else if cfg!(feature = "variant_betanet") {
+    3
+} else {
+    1
+};
+
+const ADDRESS_LEN: usize = 20;
+// Address, chain id, decimals and ticker length.
+const HEADER_LEN: usize = ADDRESS_LEN + 8 + 1 + 1;
+
+/// Verified information about a token contract.
+pub struct TokenInfo {
+    address: [u8; ADDRESS_LEN],
+    ticker: [u8; MAX_TICKER_LEN],
+    ticker_len: usize,
+    decimals: u8,
+}
+
+impl TokenInfo {
+    pub fn address(&self) -> &[u8; ADDRESS_LEN] {
+        &self.address
+    }
+
+    pub fn ticker(&self) -> &str {
+        // Tickers are checked to be alphanumeric ASCII when parsed
+        core::str::from_utf8(&self.ticker[..self.ticker_len]).unwrap_or("")
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+}
+
+impl TryFrom<&[u8]> for TokenInfo {
+    type Error = AppSW;
+
+    /// Constructs a [`TokenInfo`] from an APDU-encoded descriptor, checking its signature.
+    ///
+    /// # Format
+    ///
+    /// - 20 bytes: Contract address
+    /// - 8 bytes: Big-endian chain id, must be [`CHAIN_ID`]
+    /// - 1 byte: Decimals (up to [`MAX_DECIMALS`])
+    /// - 1 byte: Ticker length (1 to [`MAX_TICKER_LEN`])
+    /// - Ticker bytes, alphanumeric ASCII only
+    /// - 1 byte: Signature length
+    /// - Signature bytes: DER-encoded signature of the Keccak256 hash of the descriptor type
+    ///   ([`DescriptorType::Token`]) followed by all previous bytes
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() < HEADER_LEN {
+            return Err(AppSW::WrongApduLength);
+        }
+        let ticker_len = data[HEADER_LEN - 1] as usize;
+        let sig_len_offset = HEADER_LEN + ticker_len;
+        if data.len() < sig_len_offset + 1
+            || data[sig_len_offset] as usize != data.len() - sig_len_offset - 1
+        {
+            return Err(AppSW::WrongApduLength);
+        }
+
+        let chain_id = u64::from_be_bytes(data[ADDRESS_LEN..ADDRESS_LEN + 8].try_into().unwrap());
+        let decimals = data[ADDRESS_LEN + 8];
+        let ticker = &data[HEADER_LEN..sig_len_offset];
+        if chain_id != CHAIN_ID
+            || decimals > MAX_DECIMALS
+            || ticker.is_empty()
+            || ticker.len() > MAX_TICKER_LEN
+            || !ticker.iter().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(AppSW::TokenInfoInvalid);
+        }
+
+        if !verify_trusted_signature(
+            DescriptorType::Token,
+            &data[..sig_len_offset],
+            &data[sig_len_offset + 1..],
+        ) {
+            return Err(AppSW::TokenInfoSignatureFail);
+        }
+
+        let mut token = TokenInfo {
+            address: [0u8; ADDRESS_LEN],
+            ticker: [0u8; MAX_TICKER_LEN],
+            ticker_len: ticker.len(),
+            decimals,
+        };
+        token.address.copy_from_slice(&data[..ADDRESS_LEN]);
+        token.ticker[..ticker.len()].copy_from_slice(ticker);
+        Ok(token)
+    }
+}
+
+/// Token descriptors provided by the host during the session.
+#[derive(Default)]
+pub struct TokenRegistry(Vec<TokenInfo>);
+
+impl TokenRegistry {
+    /// Adds a token, replacing any descriptor for the same contract. When the registry is full,
+    /// the oldest descriptor is dropped.
+    pub fn add(&mut self, token: TokenInfo) {
+        self.0.retain(|known| known.address != token.address);
+        if self.0.len() == MAX_TOKENS {
+            self.0.remove(0);
+        }
+        self.0.push(token);
+    }
+
+    /// Returns the descriptor of the token at `address`, if any.
+    pub fn find(&self, address: &[u8; ADDRESS_LEN]) -> Option<&TokenInfo> {
+        self.0.iter().find(|token| token.address == *address)
+    }
+}
@@ -0,0 +1,100 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+//! Trusted names
+//!
+//! A trusted name descriptor binds a human-readable name to an address. It is signed by the
+//! trusted authority (see [`verify_trusted_signature`]), so the name can be displayed as verified
+//! during the review of a transaction to this address.
+
+use crate::utils::{verify_trusted_signature, DescriptorType};
+use crate::AppSW;
+
+/// Maximum length of a trusted name, in bytes.
+pub const MAX_NAME_LEN: usize = 32;
+
+const ADDRESS_LEN: usize = 20;
+
+/// A verified name for an address.
+pub struct TrustedName {
+    address: [u8; ADDRESS_LEN],
+    name: [u8; MAX_NAME_LEN],
+    name_len: usize,
+}
+
+impl TrustedName {
+    pub fn address(&self) -> &[u8; ADDRESS_LEN] {
+        &self.address
+    }
+
+    pub fn name(&self) -> &str {
+        // Names are checked to be printable ASCII when parsed
+        core::str::from_utf8(&self.name[..self.name_len]).unwrap_or("")
+    }
+}
+
+impl TryFrom<&[u8]> for TrustedName {
+    type Error = AppSW;
+
+    /// Constructs a [`TrustedName`] from an APDU-encoded descriptor, checking its signature.
+    ///
+    /// # Format
+    ///
+    /// - 20 bytes: Address
+    /// - 1 byte: Name length (1 to [`MAX_NAME_LEN`])
+    /// - Name bytes, printable ASCII only
+    /// - 1 byte: Signature length
+    /// - Signature bytes: DER-encoded signature of the Keccak256 hash of the descriptor type
+    ///   ([`DescriptorType::TrustedName`]) followed by all previous bytes
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() < ADDRESS_LEN + 1 {
+            return Err(AppSW::WrongApduLength);
+        }
+        let name_len = data[ADDRESS_LEN] as usize;
+        let sig_len_offset = ADDRESS_LEN + 1 + name_len;
+        if data.len() < sig_len_offset + 1
+            || data[sig_len_offset] as usize != data.len() - sig_len_offset - 1
+        {
+            return Err(AppSW::WrongApduLength);
+        }
+
+        let name = &data[ADDRESS_LEN + 1..sig_len_offset];
+        if name.is_empty()
+            || name.len() > MAX_NAME_LEN
+            || !name.iter().all(|c| c.is_ascii_graphic() || *c == b' ')
+        {
+            return Err(AppSW::TrustedNameInvalid);
+        }
+
+        if !verify_trusted_signature(
+            DescriptorType::TrustedName,
+            &data[..sig_len_offset],
+            &data[sig_len_offset + 1..],
+        ) {
+            return Err(AppSW::TrustedNameSignatureFail);
+        }
+
+        let mut trusted_name = TrustedName {
+            address: [0u8; ADDRESS_LEN],
+            name: [0u8; MAX_NAME_LEN],
+            name_len: name.len(),
+        };
+        trusted_name.address.copy_from_slice(&data[..ADDRESS_LEN]);
+        trusted_name.name[..name.len()].copy_from_slice(name);
+        Ok(trusted_name)
+    }
+}
@@ -0,0 +1,73 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+//! Transaction
+//!
+//! The transaction signed by the SIGN_TX APDU, deserialized from its JSON encoding.
+
+use crate::AppSW;
+use serde::Deserialize;
+use serde_json_core::from_slice;
+
+/// Maximum length of the memo of a transaction, in bytes.
+pub const MAX_MEMO_LEN: usize = 400;
+
+#[derive(Deserialize)]
+pub struct Tx<'a> {
+    pub nonce: u64,
+    pub coin: &'a str,
+    /// Amount in the smallest unit of the coin or token. It is kept as a u64, the range of the
+    /// JSON numbers sent by the host and of the spending limits, while the Exchange sends u128
+    /// amounts: a swap amount over `u64::MAX` is rejected with `AmountCastFail`.
+    pub value: u64,
+    /// Fees paid in the native coin, in its smallest unit. Absent for transactions without fees.
+    #[serde(default)]
+    pub fee: u64,
+    #[serde(with = "hex::serde")] // Allows JSON deserialization from hex string
+    pub to: [u8; 20],
+    pub memo: &'a str,
+    /// Contract address of the transferred token, as an hex string. Absent for transfers of the
+    /// native coin.
+    #[serde(default)]
+    pub token: Option<&'a str>,
+}
+
+impl<'a> Tx<'a> {
+    /// Deserializes a transaction from its JSON encoding.
+    ///
+    /// Returns [`AppSW::TxParsingFail`] if it cannot be decoded, and [`AppSW::MemoTooLong`] if
+    /// its memo is longer than [`MAX_MEMO_LEN`] bytes.
+    pub fn parse(raw_tx: &'a [u8]) -> Result<Self, AppSW> {
+        let (tx, _) = from_slice::<Tx>(raw_tx).map_err(|_| AppSW::TxParsingFail)?;
+        if tx.memo.len() > MAX_MEMO_LEN {
+            return Err(AppSW::MemoTooLong);
+        }
+        Ok(tx)
+    }
+
+    /// Decodes the contract address of the transferred token, if any.
+    ///
+    /// Returns [`AppSW::TokenAddressInvalid`] if it is not a hex encoded address.
+    pub fn token_address(&self) -> Result<Option<[u8; 20]>, AppSW> {
+        let Some(token) = self.token else {
+            return Ok(None);
+        };
+        let mut address = [0u8; 20];
+        hex::decode_to_slice(token, &mut address).map_err(|_| AppSW::TokenAddressInvalid)?;
+        Ok(Some(address))
+    }
+}
@@ -1,7 +1,10 @@
+use alloc::format;
+use alloc::string::String;
 use alloc::vec::Vec;
+use core::fmt;
 
 use crate::AppSW;
-use ledger_device_sdk::ecc::{Secp256k1, SeedDerive};
+use ledger_device_sdk::ecc::{CurvesId, ECPublicKey, Secp256k1, SeedDerive};
 use ledger_device_sdk::hash::{sha3::Keccak256, HashInit};
 
 /// BIP32 derivation path stored as a vector of u32 components.
@@ -17,6 +20,21 @@ impl AsRef<[u32]> for Bip32Path {
     }
 }
 
+impl fmt::Display for Bip32Path {
+    /// Formats the path in the usual `m/44'/1'/0'/0/0` notation, hardened components being
+    /// suffixed with an apostrophe.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("m")?;
+        for component in &self.0 {
+            write!(f, "/{}", component & 0x7FFF_FFFF)?;
+            if component & 0x8000_0000 != 0 {
+                f.write_str("'")?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl TryFrom<&[u8]> for Bip32Path {
     type Error = AppSW;
 
@@ -56,6 +74,19 @@ impl TryFrom<&[u8]> for Bip32Path {
     }
 }
 
+impl Bip32Path {
+    /// Returns the account index of a standard path, `m/44'/1'/account'/0/0`, or `None` for any
+    /// other path.
+    pub fn account_index(&self) -> Option<u32> {
+        match self.0[..] {
+            [0x8000_002C, 0x8000_0001, account, 0, 0] if account & 0x8000_0000 != 0 => {
+                Some(account & 0x7FFF_FFFF)
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Derive the raw public key from a BIP32 path.
 ///
 /// Returns the uncompressed secp256k1 public key (65 bytes):
@@ -108,3 +139,117 @@ pub fn get_address_hash_from_pubkey(pubkey: &[u8; 65]) -> [u8; 32] {
     let _ = keccak256.hash(&pubkey[1..], &mut address);
     address
 }
+
+/// Derive the 20-byte address of the key at a BIP32 path.
+///
+/// # Used by
+///
+/// - `handler_sign_tx` and `handler_parse_tx`: For the "From" field of the review, and the
+///   risk assessment
+///
+/// # Arguments
+///
+/// * `path` - BIP32 derivation path
+pub fn get_address_from_path(path: &Bip32Path) -> Result<[u8; 20], AppSW> {
+    let pubkey = get_pubkey_from_path(path)?;
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&get_address_hash_from_pubkey(&pubkey)[12..]);
+    Ok(address)
+}
+
+/// Public key of the authority trusted to sign descriptors sent by the host.
+///
+/// This is the Ledger test key, which also signs the coin configurations given to the Exchange
+/// app in the swap tests (`tests/swap/cal_helper.py`). A production application must replace it
+/// with the key of its own signing authority.
+const TRUSTED_DESCRIPTOR_PUBKEY: [u8; 65] = [
+    0x04, 0x20, 0xda, 0x62, 0x00, 0x3c, 0x0c, 0xe0, 0x97, 0xe3, 0x36, 0x44, 0xa1, 0x0f, 0xe4, 0xc3,
+    0x04, 0x54, 0x06, 0x9a, 0x44, 0x54, 0xf0, 0xfa, 0x9d, 0x4e, 0x84, 0xf4, 0x50, 0x91, 0x42, 0x9b,
+    0x52, 0x20, 0xaf, 0x9e, 0x35, 0xc0, 0xb2, 0xd9, 0x28, 0x93, 0x80, 0x13, 0x73, 0x07, 0xde, 0x4d,
+    0xd1, 0xd4, 0x18, 0x42, 0x8c, 0xf2, 0x1a, 0x93, 0xb3, 0x35, 0x61, 0xbb, 0x09, 0xd8, 0x8f, 0xe5,
+    0x79,
+];
+
+/// Kind of a descriptor signed by the trusted authority.
+///
+/// The type is hashed before the payload, so that a descriptor of one kind can never be accepted
+/// as a descriptor of another kind.
+#[derive(Clone, Copy)]
+#[repr(u8)]
+pub enum DescriptorType {
+    TrustedName = 0x01,
+    Token = 0x02,
+}
+
+/// Verify that a descriptor sent by the host was signed by the trusted authority.
+///
+/// # Arguments
+///
+/// * `descriptor_type` - Kind of descriptor expected by the caller
+/// * `payload` - Signed part of the descriptor
+/// * `signature` - DER-encoded ECDSA signature of the Keccak256 hash of the descriptor type byte
+///   followed by `payload`
+///
+/// # Returns
+///
+/// `true` if the signature is valid
+pub fn verify_trusted_signature(
+    descriptor_type: DescriptorType,
+    payload: &[u8],
+    signature: &[u8],
+) -> bool {
+    let mut keccak256 = Keccak256::new();
+    let mut hash: [u8; 32] = [0u8; 32];
+    if keccak256.update(&[descriptor_type as u8]).is_err()
+        || keccak256.update(payload).is_err()
+        || keccak256.finalize(&mut hash).is_err()
+    {
+        return false;
+    }
+
+    let mut trusted_key = ECPublicKey::<65, 'W'>::new(CurvesId::Secp256k1);
+    trusted_key.pubkey = TRUSTED_DESCRIPTOR_PUBKEY;
+    trusted_key.verify((signature, signature.len() as u32), &hash)
+}
+
+/// Format an integer amount of the smallest unit of a token as a decimal number.
+///
+/// Trailing zeros of the fractional part are removed, e.g. `1500000` with 6 decimals is
+/// formatted as `1.5`, and `2000000` as `2`.
+///
+/// # Arguments
+///
+/// * `value` - Amount in the smallest unit of the token
+/// * `decimals` - Number of decimals of the token
+pub fn format_amount(value: u64, decimals: u8) -> String {
+    let decimals = decimals as usize;
+    // Left pad with zeros so that there is at least one digit before the decimal point
+    let digits = format!("{:0>width$}", value, width = decimals + 1);
+    let (int_part, frac_part) = digits.split_at(digits.len() - decimals);
+    let frac_part = frac_part.trim_end_matches('0');
+    if frac_part.is_empty() {
+        String::from(int_part)
+    } else {
+        format!("{}.{}", int_part, frac_part)
+    }
+}
+
+/// Returns true if `text` only holds printable ASCII characters, space included.
+pub fn is_printable_ascii(text: &str) -> bool {
+    text.bytes().all(|byte| (0x20..0x7F).contains(&byte))
+}
+
+/// Escapes the characters of untrusted `text` that are not printable ASCII, as well as the
+/// backslash, with their `\u{...}` notation. Control, bidirectional override and zero width
+/// characters are made visible instead of altering the rendering of the text.
+pub fn escape_non_printable(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if (' '..='~').contains(&c) && c != '\\' {
+            escaped.push(c);
+        } else {
+            escaped.extend(c.escape_unicode());
+        }
+    }
+    escaped
+}
@@ -1,10 +1,13 @@
-from enum import IntEnum
+from enum import IntEnum, IntFlag
 from typing import Generator, List, Optional
 from contextlib import contextmanager
 
 from ragger.backend.interface import BackendInterface, RAPDU
 from ragger.bip import pack_derivation_path
 
+# Status words, generated from the table of src/status_words.rs. Re-exported for the tests.
+from .boilerplate_status_words import Errors
+
 
 MAX_APDU_LEN: int = 255
 
@@ -25,6 +28,10 @@ class P2(IntEnum):
     P2_LAST = 0x00
     # Parameter 2 for more APDU to receive.
     P2_MORE = 0x80
+    # Parameter 2 flag of the first SIGN_TX APDU, to get the transaction hash with the signature.
+    P2_RETURN_HASH = 0x01
+    # Parameter 2 flag of the first SIGN_TX APDU, to get the risk flags with the signature.
+    P2_RETURN_RISK = 0x02
 
 
 class InsType(IntEnum):
@@ -32,30 +39,66 @@ class InsType(IntEnum):
     GET_APP_NAME = 0x04
     GET_PUBLIC_KEY = 0x05
     SIGN_TX = 0x06
+    SET_SPENDING_POLICY = 0x07
+    ADD_ADDRESS_BOOK_ENTRY = 0x08
+    PROVIDE_TRUSTED_NAME = 0x09
+    PROVIDE_TOKEN_INFO = 0x0A
+    PARSE_TX = 0x0B
+    SIGN_TX_BATCH = 0x0C
+    GET_BATCH_SIGNATURE = 0x0D
+    REMOVE_ADDRESS_BOOK_ENTRY = 0x0E
+
+
+# Tags of the fields in the PARSE_TX response. The signing account is shown after the destination.
+class TxField(IntEnum):
+    AMOUNT = 0x01
+    TOKEN = 0x02
+    DESTINATION = 0x03
+    MEMO = 0x04
+    NONCE = 0x05
+    PATH = 0x06
+    HASH = 0x07
+    FROM = 0x08
+    FEES = 0x09
 
 
-class Errors(IntEnum):
-    SW_DENY = 0x6985
-    SW_WRONG_P1P2 = 0x6A86
-    SW_INS_NOT_SUPPORTED = 0x6D00
-    SW_CLA_NOT_SUPPORTED = 0x6E00
-    SW_WRONG_APDU_LENGTH = 0x6E03
-    SW_WRONG_RESPONSE_LENGTH = 0xB000
-    SW_DISPLAY_BIP32_PATH_FAIL = 0xB001
-    SW_DISPLAY_ADDRESS_FAIL = 0xB002
-    SW_DISPLAY_AMOUNT_FAIL = 0xB003
-    SW_WRONG_TX_LENGTH = 0xB004
-    SW_TX_PARSING_FAIL = 0xB005
-    SW_TX_HASH_FAIL = 0xB006
-    SW_BAD_STATE = 0xB007
-    SW_SIGNATURE_FAIL = 0xB008
-    SW_SWAP_FAIL = 0xC000
+# Risk flags returned with the SIGN_TX signature, see src/risk.rs
+class RiskFlags(IntFlag):
+    SELF_TRANSFER = 0x01
+    ZERO_VALUE = 0x02
+    UNVERIFIED_TOKEN = 0x04
+    HIGH_VALUE = 0x08
 
 
 def split_message(message: bytes, max_size: int) -> List[bytes]:
     return [message[x : x + max_size] for x in range(0, len(message), max_size)]
 
 
+# Serialize spending limits as expected by SET_SPENDING_POLICY:
+# max_per_tx (8, big endian) || cumulative_limit (8, big endian, 0 for none) || action (1)
+def pack_spending_policy(
+    max_per_tx: int,
+    cumulative_limit: int,
+    refuse: bool,
+    high_value_threshold: Optional[int] = None,
+) -> bytes:
+    policy = (
+        max_per_tx.to_bytes(8, byteorder="big")
+        + cumulative_limit.to_bytes(8, byteorder="big")
+        + (b"\x01" if refuse else b"\x00")
+    )
+    if high_value_threshold is not None:
+        policy += high_value_threshold.to_bytes(8, byteorder="big")
+    return policy
+
+
+# Serialize an address book entry as expected by ADD_ADDRESS_BOOK_ENTRY:
+# address (20) || label_len (1) || label (var)
+def pack_address_book_entry(address: str, label: str) -> bytes:
+    label_raw = label.encode("ascii")
+    return bytes.fromhex(address) + len(label_raw).to_bytes(1, byteorder="big") + label_raw
+
+
 class BoilerplateCommandSender:
     def __init__(self, backend: BackendInterface) -> None:
         self.backend = backend
@@ -102,12 +145,23 @@ class BoilerplateCommandSender:
             yield response
 
     @contextmanager
-    def sign_tx(self, path: str, transaction: bytes) -> Generator[None, None, None]:
+    def sign_tx(
+        self,
+        path: str,
+        transaction: bytes,
+        return_hash: bool = False,
+        return_risk: bool = False,
+    ) -> Generator[None, None, None]:
+        p2 = P2.P2_MORE
+        if return_hash:
+            p2 |= P2.P2_RETURN_HASH
+        if return_risk:
+            p2 |= P2.P2_RETURN_RISK
         self.backend.exchange(
             cla=CLA,
             ins=InsType.SIGN_TX,
             p1=P1.P1_START,
-            p2=P2.P2_MORE,
+            p2=p2,
             data=pack_derivation_path(path),
         )
         messages = split_message(transaction, MAX_APDU_LEN)
@@ -124,6 +178,132 @@ class BoilerplateCommandSender:
         ) as response:
             yield response
 
+    @contextmanager
+    def set_spending_policy(
+        self,
+        max_per_tx: int,
+        cumulative_limit: int,
+        refuse: bool,
+        high_value_threshold: Optional[int] = None,
+    ) -> Generator[None, None, None]:
+        with self.backend.exchange_async(
+            cla=CLA,
+            ins=InsType.SET_SPENDING_POLICY,
+            p1=P1.P1_START,
+            p2=P2.P2_LAST,
+            data=pack_spending_policy(
+                max_per_tx, cumulative_limit, refuse, high_value_threshold
+            ),
+        ) as response:
+            yield response
+
+    @contextmanager
+    def add_address_book_entry(
+        self, address: str, label: str
+    ) -> Generator[None, None, None]:
+        with self.backend.exchange_async(
+            cla=CLA,
+            ins=InsType.ADD_ADDRESS_BOOK_ENTRY,
+            p1=P1.P1_START,
+            p2=P2.P2_LAST,
+            data=pack_address_book_entry(address, label),
+        ) as response:
+            yield response
+
+    @contextmanager
+    def remove_address_book_entry(self, address: str) -> Generator[None, None, None]:
+        with self.backend.exchange_async(
+            cla=CLA,
+            ins=InsType.REMOVE_ADDRESS_BOOK_ENTRY,
+            p1=P1.P1_START,
+            p2=P2.P2_LAST,
+            data=bytes.fromhex(address),
+        ) as response:
+            yield response
+
+    def provide_trusted_name(self, descriptor: bytes) -> RAPDU:
+        return self.backend.exchange(
+            cla=CLA,
+            ins=InsType.PROVIDE_TRUSTED_NAME,
+            p1=P1.P1_START,
+            p2=P2.P2_LAST,
+            data=descriptor,
+        )
+
+    def provide_token_info(self, descriptor: bytes) -> RAPDU:
+        return self.backend.exchange(
+            cla=CLA,
+            ins=InsType.PROVIDE_TOKEN_INFO,
+            p1=P1.P1_START,
+            p2=P2.P2_LAST,
+            data=descriptor,
+        )
+
+    # Same chunks as sign_tx, the formatted review fields are returned without any screen
+    def parse_tx(self, path: str, transaction: bytes) -> RAPDU:
+        self.backend.exchange(
+            cla=CLA,
+            ins=InsType.PARSE_TX,
+            p1=P1.P1_START,
+            p2=P2.P2_MORE,
+            data=pack_derivation_path(path),
+        )
+        messages = split_message(transaction, MAX_APDU_LEN)
+        idx: int = P1.P1_START + 1
+
+        for msg in messages[:-1]:
+            self.backend.exchange(
+                cla=CLA, ins=InsType.PARSE_TX, p1=idx, p2=P2.P2_MORE, data=msg
+            )
+            idx += 1
+
+        return self.backend.exchange(
+            cla=CLA, ins=InsType.PARSE_TX, p1=idx, p2=P2.P2_LAST, data=messages[-1]
+        )
+
+    # One transaction per APDU, after the derivation path. The review of the batch is shown
+    # after the last one, the signatures are then read with get_batch_signature
+    @contextmanager
+    def sign_tx_batch(
+        self, path: str, transactions: List[bytes]
+    ) -> Generator[None, None, None]:
+        self.backend.exchange(
+            cla=CLA,
+            ins=InsType.SIGN_TX_BATCH,
+            p1=P1.P1_START,
+            p2=P2.P2_MORE,
+            data=pack_derivation_path(path),
+        )
+        idx: int = P1.P1_START + 1
+
+        for transaction in transactions[:-1]:
+            self.backend.exchange(
+                cla=CLA,
+                ins=InsType.SIGN_TX_BATCH,
+                p1=idx,
+                p2=P2.P2_MORE,
+                data=transaction,
+            )
+            idx += 1
+
+        with self.backend.exchange_async(
+            cla=CLA,
+            ins=InsType.SIGN_TX_BATCH,
+            p1=idx,
+            p2=P2.P2_LAST,
+            data=transactions[-1],
+        ) as response:
+            yield response
+
+    def get_batch_signature(self, index: int) -> RAPDU:
+        return self.backend.exchange(
+            cla=CLA,
+            ins=InsType.GET_BATCH_SIGNATURE,
+            p1=index,
+            p2=P2.P2_LAST,
+            data=b"",
+        )
+
     def get_async_response(self) -> Optional[RAPDU]:
         return self.backend.last_async_response
 
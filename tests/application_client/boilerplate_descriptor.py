@@ -0,0 +1,68 @@
+from sha3 import keccak_256
+
+from ecdsa.curves import SECP256k1
+from ecdsa.keys import SigningKey
+from ecdsa.util import sigencode_der
+
+
+# Private part of the Ledger test key, which also signs the coin configurations of the swap tests
+# (see tests/swap/cal_helper.py). Its public part is TRUSTED_DESCRIPTOR_PUBKEY in src/utils.rs.
+TRUSTED_DESCRIPTOR_TEST_KEY: bytes = bytes.fromhex(
+    "b1ed47ef58f782e2bc4d5abe70ef66d9009c2957967017054470e0f3e10f5833"
+)
+
+# Descriptor types, see DescriptorType in src/utils.rs
+DESCRIPTOR_TYPE_TRUSTED_NAME: int = 0x01
+DESCRIPTOR_TYPE_TOKEN: int = 0x02
+
+
+# Sign a descriptor payload with the test key and append the signature:
+# payload (var) || sig_len (1) || der_sig (var)
+# The descriptor type is signed before the payload but is not sent.
+def sign_descriptor(
+    descriptor_type: int, payload: bytes, key: bytes = TRUSTED_DESCRIPTOR_TEST_KEY
+) -> bytes:
+    sk: SigningKey = SigningKey.from_string(key, curve=SECP256k1)
+    signature = sk.sign_deterministic(
+        descriptor_type.to_bytes(1, byteorder="big") + payload,
+        hashfunc=keccak_256,
+        sigencode=sigencode_der,
+    )
+    return payload + len(signature).to_bytes(1, byteorder="big") + signature
+
+
+# Serialize a trusted name descriptor as expected by PROVIDE_TRUSTED_NAME:
+# address (20) || name_len (1) || name (var) || sig_len (1) || der_sig (var)
+def pack_trusted_name(
+    address: str, name: str, key: bytes = TRUSTED_DESCRIPTOR_TEST_KEY
+) -> bytes:
+    name_raw = name.encode("ascii")
+    payload = (
+        bytes.fromhex(address) + len(name_raw).to_bytes(1, byteorder="big") + name_raw
+    )
+    return sign_descriptor(DESCRIPTOR_TYPE_TRUSTED_NAME, payload, key)
+
+
+# Chain id of the default app variant, see CHAIN_ID in src/token.rs
+CHAIN_ID: int = 1
+
+
+# Serialize a token descriptor as expected by PROVIDE_TOKEN_INFO:
+# address (20) || chain_id (8, big endian) || decimals (1) || ticker_len (1) || ticker (var)
+# || sig_len (1) || der_sig (var)
+def pack_token_info(
+    address: str,
+    ticker: str,
+    decimals: int,
+    chain_id: int = CHAIN_ID,
+    key: bytes = TRUSTED_DESCRIPTOR_TEST_KEY,
+) -> bytes:
+    ticker_raw = ticker.encode("ascii")
+    payload = (
+        bytes.fromhex(address)
+        + chain_id.to_bytes(8, byteorder="big")
+        + decimals.to_bytes(1, byteorder="big")
+        + len(ticker_raw).to_bytes(1, byteorder="big")
+        + ticker_raw
+    )
+    return sign_descriptor(DESCRIPTOR_TYPE_TOKEN, payload, key)
@@ -1,4 +1,4 @@
-from typing import Tuple
+from typing import List, Tuple
 from struct import unpack
 
 
@@ -74,3 +74,47 @@ def unpack_sign_tx_response(response: bytes) -> Tuple[int, bytes, int]:
     assert len(response) == 0
 
     return der_sig_len, der_sig, int.from_bytes(v, byteorder="big")
+
+
+# Unpack from response:
+# response = der_sig_len (1)
+#            der_sig (var)
+#            v (1)
+#            tx_hash (32)
+def unpack_sign_tx_response_with_hash(response: bytes) -> Tuple[int, bytes, int, bytes]:
+    response, der_sig_len, der_sig = pop_size_prefixed_buf_from_buf(response)
+    response, v = pop_sized_buf_from_buffer(response, 1)
+    response, tx_hash = pop_sized_buf_from_buffer(response, 32)
+
+    assert len(tx_hash) == 32
+    assert len(response) == 0
+
+    return der_sig_len, der_sig, int.from_bytes(v, byteorder="big"), tx_hash
+
+
+# Unpack from response:
+# response = der_sig_len (1)
+#            der_sig (var)
+#            v (1)
+#            risk_flags (1)
+def unpack_sign_tx_response_with_risk(response: bytes) -> Tuple[int, bytes, int, int]:
+    response, der_sig_len, der_sig = pop_size_prefixed_buf_from_buf(response)
+    response, v = pop_sized_buf_from_buffer(response, 1)
+    response, risk_flags = pop_sized_buf_from_buffer(response, 1)
+
+    assert len(response) == 0
+
+    return der_sig_len, der_sig, int.from_bytes(v, byteorder="big"), risk_flags[0]
+
+
+# Unpack from response, for each field:
+# response = tag (1)
+#            value_len (1)
+#            value (var)
+def unpack_parse_tx_response(response: bytes) -> List[Tuple[int, str]]:
+    fields = []
+    while len(response) > 0:
+        response, tag = pop_sized_buf_from_buffer(response, 1)
+        response, _, value = pop_size_prefixed_buf_from_buf(response)
+        fields.append((tag[0], value.decode("utf-8")))
+    return fields
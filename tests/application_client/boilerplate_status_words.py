@@ -0,0 +1,85 @@
+# Status words of the application.
+# Generated by build.rs from src/status_words.rs, do not edit.
+
+from enum import IntEnum
+
+
+class Errors(IntEnum):
+    # The user rejected the request.
+    SW_DENY = 0x6985
+    # P1 or P2 is not valid for the instruction.
+    SW_WRONG_P1P2 = 0x6A86
+    # The instruction is not supported.
+    SW_INS_NOT_SUPPORTED = 0x6D00
+    # The class is not supported. Same value as the SDK `StatusWords::BadCla`, sent by
+    # the SDK, see `Comm::set_expected_cla`.
+    SW_CLA_NOT_SUPPORTED = 0x6E00
+    # The length of the APDU, or of its data, is not valid. Same value as the SDK
+    # `StatusWords::BadLen`, sent by the SDK for truncated APDUs (checked in `main.rs`).
+    SW_WRONG_APDU_LENGTH = 0x6E03
+    # The APDU exchange with the host failed.
+    SW_COMM_ERROR = 0x6F00
+    # The response does not fit in the APDU buffer (`CommError::Overflow`).
+    SW_WRONG_RESPONSE_LENGTH = 0xB000
+    # The derivation path could not be displayed. Not sent by this application, kept
+    # for the clients of the other boilerplate applications.
+    SW_DISPLAY_BIP32_PATH_FAIL = 0xB001
+    # The address could not be displayed.
+    SW_DISPLAY_ADDRESS_FAIL = 0xB002
+    # The amount of the transaction could not be displayed: its coin ticker is too long.
+    SW_DISPLAY_AMOUNT_FAIL = 0xB003
+    # The transaction is longer than `MAX_TRANSACTION_LEN`.
+    SW_WRONG_TX_LENGTH = 0xB004
+    # The transaction could not be parsed, and blind signing is disabled.
+    SW_TX_PARSING_FAIL = 0xB005
+    # The transaction could not be hashed. Not sent by this application, kept for the
+    # clients of the other boilerplate applications.
+    SW_TX_HASH_FAIL = 0xB006
+    # A SIGN_TX chunk was received out of sequence: without the derivation path chunk,
+    # or not right after the previous chunk.
+    SW_BAD_STATE = 0xB007
+    # The transaction could not be signed.
+    SW_SIGNATURE_FAIL = 0xB008
+    # The public key or the chain code could not be derived.
+    SW_KEY_DERIVE_FAIL = 0xB009
+    # The version of the application could not be parsed.
+    SW_VERSION_PARSING_FAIL = 0xB00A
+    # The spending policy could not be parsed.
+    SW_POLICY_PARSING_FAIL = 0xB00B
+    # The transaction is over the spending limits, and the policy refuses it.
+    SW_SPENDING_LIMIT_EXCEEDED = 0xB00C
+    # The address book entry could not be parsed.
+    SW_ADDRESS_BOOK_ENTRY_INVALID = 0xB00D
+    # The address book is full.
+    SW_ADDRESS_BOOK_FULL = 0xB00E
+    # The address book is disabled in the settings.
+    SW_ADDRESS_BOOK_DISABLED = 0xB00F
+    # The trusted name descriptor could not be parsed.
+    SW_TRUSTED_NAME_INVALID = 0xB010
+    # The signature of the trusted name descriptor is not valid.
+    SW_TRUSTED_NAME_SIGNATURE_FAIL = 0xB011
+    # The token descriptor could not be parsed.
+    SW_TOKEN_INFO_INVALID = 0xB012
+    # The signature of the token descriptor is not valid.
+    SW_TOKEN_INFO_SIGNATURE_FAIL = 0xB013
+    # The batch already holds `MAX_BATCH_SIZE` transactions.
+    SW_BATCH_TOO_LARGE = 0xB014
+    # The total value of the batch is over `MAX_BATCH_TOTAL`, or its fees do not fit in
+    # 64 bits.
+    SW_BATCH_VALUE_TOO_HIGH = 0xB015
+    # Token transfers cannot be signed in a batch.
+    SW_BATCH_TOKEN_TRANSFER = 0xB016
+    # The memo of the transaction is longer than `MAX_MEMO_LEN`.
+    SW_MEMO_TOO_LONG = 0xB017
+    # No contact is saved for the address to remove from the address book.
+    SW_ADDRESS_BOOK_ENTRY_NOT_FOUND = 0xB018
+    # The transactions of a batch must have distinct nonces.
+    SW_BATCH_DUPLICATE_NONCE = 0xB01A
+    # The label of the address book entry is already saved, for the same or another
+    # address.
+    SW_ADDRESS_BOOK_DUPLICATE = 0xB01B
+    # The token address of the transaction is not a hex encoded address.
+    SW_TOKEN_ADDRESS_INVALID = 0xB01C
+    # The transaction does not match the swap parameters. The swap error is in the
+    # response data.
+    SW_SWAP_FAIL = 0xC000
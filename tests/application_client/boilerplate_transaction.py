@@ -1,5 +1,6 @@
 import json
 from dataclasses import dataclass
+from typing import Optional
 from .boilerplate_utils import UINT64_MAX
 
 
@@ -14,6 +15,10 @@ class Transaction:
     value: str
     to: str
     memo: str
+    # Contract address of the transferred token, None for the native coin
+    token: Optional[str] = None
+    # Fees paid in the native coin, not serialized when there are none
+    fee: int = 0
 
     def serialize(self) -> bytes:
         if not 0 <= self.nonce <= UINT64_MAX:
@@ -22,13 +27,23 @@ class Transaction:
         if len(self.to) != 40:
             raise TransactionError(f"Bad address: '{self.to}'!")
 
+        if not 0 <= self.fee <= UINT64_MAX:
+            raise TransactionError(f"Bad fee: '{self.fee}'!")
+
+        if self.token is not None and len(self.token) != 40:
+            raise TransactionError(f"Bad token address: '{self.token}'!")
+
+        tx = {
+            "nonce": self.nonce,
+            "coin": self.coin,
+            "value": self.value,
+            "to": self.to,
+            "memo": self.memo,
+        }
+        if self.token is not None:
+            tx["token"] = self.token
+        if self.fee:
+            tx["fee"] = self.fee
+
         # Serialize the transaction data to a JSON-formatted string
-        return json.dumps(
-            {
-                "nonce": self.nonce,
-                "coin": self.coin,
-                "value": self.value,
-                "to": self.to,
-                "memo": self.memo,
-            }
-        ).encode("utf-8")
+        return json.dumps(tx).encode("utf-8")
@@ -0,0 +1,134 @@
+import pytest
+
+from application_client.boilerplate_command_sender import (
+    BoilerplateCommandSender,
+    CLA,
+    InsType,
+    P1,
+    P2,
+    Errors,
+    pack_address_book_entry,
+)
+from ragger.error import ExceptionRAPDU
+
+from .utils import Setting, toggle_setting
+
+# In these tests we check the behavior of the device when asked to save an address book entry
+
+ADDRESS: str = "de0b295669a9fd93d5f28d9ec85e40f4cb697bae"
+
+# Same value as ADDRESS_BOOK_ENTRIES in src/address_book.rs
+ADDRESS_BOOK_ENTRIES: int = 10
+
+
+# The address book is disabled in the default settings: nothing is displayed
+def test_add_address_book_entry_disabled(backend):
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(
+            cla=CLA,
+            ins=InsType.ADD_ADDRESS_BOOK_ENTRY,
+            p1=P1.P1_START,
+            p2=P2.P2_LAST,
+            data=pack_address_book_entry(ADDRESS, "Alice"),
+        )
+    assert e.value.status == Errors.SW_ADDRESS_BOOK_DISABLED
+
+
+# Ensure the app rejects malformed entries without displaying anything
+def test_add_address_book_entry_malformed(backend):
+    # Label length does not match the data length
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(
+            cla=CLA,
+            ins=InsType.ADD_ADDRESS_BOOK_ENTRY,
+            p1=P1.P1_START,
+            p2=P2.P2_LAST,
+            data=pack_address_book_entry(ADDRESS, "Alice")[:-1],
+        )
+    assert e.value.status == Errors.SW_WRONG_APDU_LENGTH
+
+    # Label with a non printable character
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(
+            cla=CLA,
+            ins=InsType.ADD_ADDRESS_BOOK_ENTRY,
+            p1=P1.P1_START,
+            p2=P2.P2_LAST,
+            data=pack_address_book_entry(ADDRESS, "Ali\tce"),
+        )
+    assert e.value.status == Errors.SW_ADDRESS_BOOK_ENTRY_INVALID
+
+    # Label too long
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(
+            cla=CLA,
+            ins=InsType.ADD_ADDRESS_BOOK_ENTRY,
+            p1=P1.P1_START,
+            p2=P2.P2_LAST,
+            data=pack_address_book_entry(ADDRESS, "A" * 21),
+        )
+    assert e.value.status == Errors.SW_ADDRESS_BOOK_ENTRY_INVALID
+
+
+# A label already saved, or a full address book, is refused before the review of the entry
+def test_add_address_book_entry_duplicate_and_full(
+    backend, scenario_navigator, device, navigator
+):
+    if device.is_nano:
+        pytest.skip("Skipping this test for Nano devices")
+
+    client = BoilerplateCommandSender(backend)
+    toggle_setting(device, navigator, Setting.ADDRESS_BOOK)
+
+    with client.add_address_book_entry(ADDRESS, "Alice"):
+        scenario_navigator.review_approve(do_comparison=False)
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(
+            cla=CLA,
+            ins=InsType.ADD_ADDRESS_BOOK_ENTRY,
+            p1=P1.P1_START,
+            p2=P2.P2_LAST,
+            data=pack_address_book_entry("11" * 20, "Alice"),
+        )
+    assert e.value.status == Errors.SW_ADDRESS_BOOK_DUPLICATE
+
+    for index in range(1, ADDRESS_BOOK_ENTRIES):
+        with client.add_address_book_entry(f"{index:040x}", f"Contact {index}"):
+            scenario_navigator.review_approve(do_comparison=False)
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(
+            cla=CLA,
+            ins=InsType.ADD_ADDRESS_BOOK_ENTRY,
+            p1=P1.P1_START,
+            p2=P2.P2_LAST,
+            data=pack_address_book_entry("11" * 20, "Bob"),
+        )
+    assert e.value.status == Errors.SW_ADDRESS_BOOK_FULL
+
+
+# Entries cannot be removed while the address book is disabled in the settings
+def test_remove_address_book_entry_disabled(backend):
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(
+            cla=CLA,
+            ins=InsType.REMOVE_ADDRESS_BOOK_ENTRY,
+            p1=P1.P1_START,
+            p2=P2.P2_LAST,
+            data=bytes.fromhex(ADDRESS),
+        )
+    assert e.value.status == Errors.SW_ADDRESS_BOOK_DISABLED
+
+
+# The address to remove is exactly 20 bytes
+def test_remove_address_book_entry_malformed(backend):
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(
+            cla=CLA,
+            ins=InsType.REMOVE_ADDRESS_BOOK_ENTRY,
+            p1=P1.P1_START,
+            p2=P2.P2_LAST,
+            data=bytes.fromhex(ADDRESS)[:-1],
+        )
+    assert e.value.status == Errors.SW_WRONG_APDU_LENGTH
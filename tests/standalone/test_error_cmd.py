@@ -1,5 +1,6 @@
 import pytest
 
+from ragger.bip import pack_derivation_path
 from ragger.error import ExceptionRAPDU
 from application_client.boilerplate_command_sender import CLA, InsType, P1, P2, Errors
 
@@ -55,11 +56,27 @@ def test_wrong_data_length(backend):
 
 
 # Ensure there is no state confusion when trying wrong APDU sequences
-# def test_invalid_state(backend):
-#     with pytest.raises(ExceptionRAPDU) as e:
-#         backend.exchange(cla=CLA,
-#                          ins=InsType.SIGN_TX,
-#                          p1=P1.P1_START + 1,  # Try to continue a flow instead of start a new one
-#                          p2=P2.P2_MORE,
-#                          data=b"abcde")  # data is not parsed in this case
-#     assert e.value.status == Errors.SW_BAD_STATE
+def test_invalid_state(backend):
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(cla=CLA,
+                         ins=InsType.SIGN_TX,
+                         p1=P1.P1_START + 1,  # Try to continue a flow instead of start a new one
+                         p2=P2.P2_MORE,
+                         data=b"abcde")  # data is not parsed in this case
+    assert e.value.status == Errors.SW_BAD_STATE
+
+
+# Ensure the chunks of a transaction cannot be skipped
+def test_skipped_chunk(backend):
+    backend.exchange(cla=CLA,
+                     ins=InsType.SIGN_TX,
+                     p1=P1.P1_START,
+                     p2=P2.P2_MORE,
+                     data=pack_derivation_path("m/44'/1'/0'/0/0"))
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(cla=CLA,
+                         ins=InsType.SIGN_TX,
+                         p1=P1.P1_START + 2,  # Skip the first chunk of the transaction
+                         p2=P2.P2_LAST,
+                         data=b"abcde")
+    assert e.value.status == Errors.SW_BAD_STATE
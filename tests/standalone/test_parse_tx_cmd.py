@@ -0,0 +1,201 @@
+import pytest
+
+from application_client.boilerplate_command_sender import (
+    CLA,
+    P1,
+    P2,
+    BoilerplateCommandSender,
+    Errors,
+    InsType,
+    TxField,
+)
+from application_client.boilerplate_descriptor import pack_token_info
+from application_client.boilerplate_response_unpacker import (
+    unpack_get_public_key_response,
+    unpack_parse_tx_response,
+)
+from application_client.boilerplate_transaction import Transaction
+from ragger.bip import pack_derivation_path
+from ragger.error import ExceptionRAPDU
+from sha3 import keccak_256
+
+from .utils import Setting, toggle_setting
+
+# In these tests we check the review fields returned by PARSE_TX, which displays nothing
+
+TOKEN_ADDRESS: str = "9fc3da866e7df3a1c57ade1a97c9f00a70f010c8"
+
+
+# The fields of a native coin transfer, with the default settings: the memo is not displayed
+def test_parse_tx(backend):
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    transaction = Transaction(
+        nonce=1,
+        coin="CRAB",
+        value=777,
+        to="de0b295669a9fd93d5f28d9ec85e40f4cb697bae",
+        memo="For u EthDev",
+    ).serialize()
+
+    rapdu = client.parse_tx(path=path, transaction=transaction)
+    assert unpack_parse_tx_response(rapdu.data) == [
+        (TxField.AMOUNT, "CRAB 777"),
+        (TxField.DESTINATION, "0xDE0B295669A9FD93D5F28D9EC85E40F4CB697BAE"),
+        (TxField.FROM, "Account 0"),
+    ]
+
+
+# The fees of a transaction are shown after its amount
+def test_parse_tx_fees(backend):
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    transaction = Transaction(
+        nonce=1,
+        coin="CRAB",
+        value=777,
+        to="de0b295669a9fd93d5f28d9ec85e40f4cb697bae",
+        memo="",
+        fee=21,
+    ).serialize()
+
+    rapdu = client.parse_tx(path=path, transaction=transaction)
+    assert unpack_parse_tx_response(rapdu.data) == [
+        (TxField.AMOUNT, "CRAB 777"),
+        (TxField.FEES, "CRAB 21"),
+        (TxField.DESTINATION, "0xDE0B295669A9FD93D5F28D9EC85E40F4CB697BAE"),
+        (TxField.FROM, "Account 0"),
+    ]
+
+
+# The signing account of a path that is not m/44'/1'/account'/0/0 is shown by its address
+def test_parse_tx_non_standard_path(backend):
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/1"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+    own_address = keccak_256(public_key[1:]).digest()[-20:].hex()
+
+    transaction = Transaction(
+        nonce=1,
+        coin="CRAB",
+        value=777,
+        to="de0b295669a9fd93d5f28d9ec85e40f4cb697bae",
+        memo="",
+    ).serialize()
+
+    rapdu = client.parse_tx(path=path, transaction=transaction)
+    assert unpack_parse_tx_response(rapdu.data) == [
+        (TxField.AMOUNT, "CRAB 777"),
+        (TxField.DESTINATION, "0xDE0B295669A9FD93D5F28D9EC85E40F4CB697BAE"),
+        (TxField.FROM, "0x" + own_address.upper()),
+    ]
+
+
+# The fields of a verified token transfer use the verified ticker and decimals
+def test_parse_tx_verified_token(backend):
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    rapdu = client.provide_token_info(pack_token_info(TOKEN_ADDRESS, "USDC", 6))
+    assert rapdu.status == 0x9000
+
+    transaction = Transaction(
+        nonce=1,
+        coin="FAKE",
+        value=1500000,
+        to="de0b295669a9fd93d5f28d9ec85e40f4cb697bae",
+        memo="",
+        token=TOKEN_ADDRESS,
+    ).serialize()
+
+    rapdu = client.parse_tx(path=path, transaction=transaction)
+    assert unpack_parse_tx_response(rapdu.data) == [
+        (TxField.AMOUNT, "USDC 1.5"),
+        (TxField.TOKEN, "0x" + TOKEN_ADDRESS.upper()),
+        (TxField.DESTINATION, "0xDE0B295669A9FD93D5F28D9EC85E40F4CB697BAE"),
+        (TxField.FROM, "Account 0"),
+    ]
+
+
+# A memo that is not printable ASCII is shown in hex, after a notice. The JSON encoding of the
+# Python client escapes non-ASCII characters, which the app does not decode: the transaction is
+# encoded by hand.
+def test_parse_tx_non_ascii_memo(backend, device, navigator):
+    if device.is_nano:
+        pytest.skip("Skipping this test for Nano devices")
+
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    # Enable display of transaction memo
+    toggle_setting(device, navigator, Setting.DISPLAY_MEMO)
+
+    # Right-to-left override, which would show "invoiceexe.pdf"
+    memo = "invoice\u202efdp.exe".encode("utf-8")
+    transaction = (
+        b'{"nonce": 1, "coin": "CRAB", "value": 777, '
+        b'"to": "de0b295669a9fd93d5f28d9ec85e40f4cb697bae", "memo": "' + memo + b'"}'
+    )
+
+    rapdu = client.parse_tx(path=path, transaction=transaction)
+    assert unpack_parse_tx_response(rapdu.data) == [
+        (TxField.AMOUNT, "CRAB 777"),
+        (TxField.DESTINATION, "0xDE0B295669A9FD93D5F28D9EC85E40F4CB697BAE"),
+        (TxField.FROM, "Account 0"),
+        (TxField.MEMO, "Non-ASCII memo, in hex:\n0x" + memo.hex().upper()),
+    ]
+
+
+# A transaction that cannot be decoded is refused, whatever the blind signing setting
+def test_parse_tx_parsing_fail(backend):
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    transaction = b'{"contract":"de0b295669a9fd93d5f28d9ec85e40f4cb697bae","data":"a9059cbb"}'
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.parse_tx(path=path, transaction=transaction)
+    assert e.value.status == Errors.SW_TX_PARSING_FAIL
+
+
+# A token transfer whose contract address is not hex encoded is refused with its own error
+def test_parse_tx_token_address_invalid(backend):
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    transaction = Transaction(
+        nonce=1,
+        coin="USDC",
+        value=1,
+        to="de0b295669a9fd93d5f28d9ec85e40f4cb697bae",
+        memo="",
+        token="z" * 40,
+    ).serialize()
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.parse_tx(path=path, transaction=transaction)
+    assert e.value.status == Errors.SW_TOKEN_ADDRESS_INVALID
+
+
+# The chunks of PARSE_TX cannot follow the derivation path sent with SIGN_TX
+def test_parse_tx_after_sign_tx_path(backend):
+    backend.exchange(
+        cla=CLA,
+        ins=InsType.SIGN_TX,
+        p1=P1.P1_START,
+        p2=P2.P2_MORE,
+        data=pack_derivation_path("m/44'/1'/0'/0/0"),
+    )
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(
+            cla=CLA,
+            ins=InsType.PARSE_TX,
+            p1=P1.P1_START + 1,
+            p2=P2.P2_LAST,
+            data=b"abcde",
+        )
+    assert e.value.status == Errors.SW_BAD_STATE
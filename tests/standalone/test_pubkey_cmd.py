@@ -9,6 +9,7 @@ from application_client.boilerplate_response_unpacker import (
 )
 from ragger.bip import calculate_public_key_and_chaincode, CurveChoice
 from ragger.error import ExceptionRAPDU
+from ragger.navigator import NavInsID
 
 
 # In this test we check that the GET_PUBLIC_KEY works in non-confirmation mode
@@ -59,3 +60,37 @@ def test_get_public_key_confirm_refused(backend, scenario_navigator):
     # Assert that we have received a refusal
     assert e.value.status == Errors.SW_DENY
     assert len(e.value.data) == 0
+
+
+# In this test we check that the address review of touchscreen devices offers a QR code page,
+# encoding the displayed address, before the address is confirmed
+def test_get_public_key_confirm_qr_code(
+    backend, device, navigator, test_name, default_screenshot_path
+):
+    if device.is_nano:
+        pytest.skip("The QR code page is only available on touchscreen devices")
+
+    client = BoilerplateCommandSender(backend)
+    path = "m/44'/1'/0'/0/0"
+
+    with client.get_public_key_with_confirmation(path=path):
+        navigator.navigate_and_compare(
+            default_screenshot_path,
+            test_name,
+            [
+                NavInsID.SWIPE_CENTER_TO_LEFT,
+                NavInsID.USE_CASE_ADDRESS_CONFIRMATION_TAP,
+                NavInsID.USE_CASE_ADDRESS_CONFIRMATION_EXIT_QR,
+                NavInsID.USE_CASE_ADDRESS_CONFIRMATION_CONFIRM,
+                NavInsID.USE_CASE_STATUS_DISMISS,
+            ],
+            screen_change_before_first_instruction=False,
+        )
+
+    response = client.get_async_response().data
+    _, public_key, _, _ = unpack_get_public_key_response(response)
+
+    ref_public_key, _ = calculate_public_key_and_chaincode(
+        CurveChoice.Secp256k1, path=path
+    )
+    assert public_key.hex() == ref_public_key
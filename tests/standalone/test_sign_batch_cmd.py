@@ -0,0 +1,298 @@
+import pytest
+
+from application_client.boilerplate_command_sender import (
+    CLA,
+    P1,
+    P2,
+    BoilerplateCommandSender,
+    Errors,
+    InsType,
+)
+from application_client.boilerplate_response_unpacker import (
+    unpack_get_public_key_response,
+    unpack_sign_tx_response,
+)
+from application_client.boilerplate_transaction import Transaction
+from application_client.utils import check_signature_validity
+from ragger.bip import pack_derivation_path
+from ragger.error import ExceptionRAPDU
+from ragger.navigator import NavInsID
+from sha3 import keccak_256
+
+# In these tests we check the signature of several transactions behind a single review
+
+# Same values as MAX_BATCH_SIZE and MAX_BATCH_TOTAL in src/batch.rs
+MAX_BATCH_SIZE: int = 8
+MAX_BATCH_TOTAL: int = 1_000_000_000_000_000
+
+DESTINATIONS = [
+    "de0b295669a9fd93d5f28d9ec85e40f4cb697bae",
+    "9fc3da866e7df3a1c57ade1a97c9f00a70f010c8",
+    "1111111111111111111111111111111111111111",
+]
+
+
+def batch_transactions(count: int, coin: str = "CRAB") -> list:
+    return [
+        Transaction(
+            nonce=index + 1,
+            coin=coin,
+            value=100 * (index + 1),
+            to=DESTINATIONS[index % len(DESTINATIONS)],
+            memo="",
+        ).serialize()
+        for index in range(count)
+    ]
+
+
+# The batch is approved once, then each signature is read and checked
+def test_sign_tx_batch(backend, scenario_navigator):
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+
+    transactions = batch_transactions(3)
+
+    with client.sign_tx_batch(path=path, transactions=transactions):
+        scenario_navigator.review_approve()
+
+    assert client.get_async_response().data == bytes([len(transactions)])
+
+    for index, transaction in enumerate(transactions):
+        rapdu = client.get_batch_signature(index)
+        _, der_sig, _ = unpack_sign_tx_response(rapdu.data)
+        assert check_signature_validity(public_key, der_sig, transaction)
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.get_batch_signature(len(transactions))
+    assert e.value.status == Errors.SW_WRONG_P1P2
+
+
+# The risks raised by the transactions of a batch are shown once each, before its review
+def test_sign_tx_batch_risk_warnings(
+    backend, scenario_navigator, device, navigator, test_name, default_screenshot_path
+):
+    if device.is_nano:
+        pytest.skip("Skipping this test for Nano devices")
+
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+    own_address = keccak_256(public_key[1:]).digest()[-20:].hex()
+
+    transactions = [
+        Transaction(nonce=1, coin="CRAB", value=100, to=own_address, memo="").serialize(),
+        Transaction(nonce=2, coin="CRAB", value=0, to=DESTINATIONS[0], memo="").serialize(),
+        Transaction(nonce=3, coin="CRAB", value=0, to=DESTINATIONS[1], memo="").serialize(),
+    ]
+
+    with client.sign_tx_batch(path=path, transactions=transactions):
+        # Continue after the self transfer and the zero value warnings
+        navigator.navigate_and_compare(
+            default_screenshot_path,
+            test_name + "_warnings",
+            [NavInsID.USE_CASE_CHOICE_CONFIRM, NavInsID.USE_CASE_CHOICE_CONFIRM],
+            screen_change_before_first_instruction=False,
+        )
+        scenario_navigator.review_approve()
+
+    assert client.get_async_response().data == bytes([len(transactions)])
+
+    for index, transaction in enumerate(transactions):
+        rapdu = client.get_batch_signature(index)
+        _, der_sig, _ = unpack_sign_tx_response(rapdu.data)
+        assert check_signature_validity(public_key, der_sig, transaction)
+
+
+# A rejected batch is not signed
+def test_sign_tx_batch_refused(backend, scenario_navigator):
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        with client.sign_tx_batch(path=path, transactions=batch_transactions(2)):
+            scenario_navigator.review_reject()
+    assert e.value.status == Errors.SW_DENY
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.get_batch_signature(0)
+    assert e.value.status == Errors.SW_BAD_STATE
+
+
+# A batch holds at most MAX_BATCH_SIZE transactions
+def test_sign_tx_batch_too_large(backend):
+    path: str = "m/44'/1'/0'/0/0"
+
+    backend.exchange(
+        cla=CLA,
+        ins=InsType.SIGN_TX_BATCH,
+        p1=P1.P1_START,
+        p2=P2.P2_MORE,
+        data=pack_derivation_path(path),
+    )
+    transactions = batch_transactions(MAX_BATCH_SIZE + 1)
+    for index, transaction in enumerate(transactions[:-1]):
+        backend.exchange(
+            cla=CLA, ins=InsType.SIGN_TX_BATCH, p1=index + 1, p2=P2.P2_MORE, data=transaction
+        )
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(
+            cla=CLA,
+            ins=InsType.SIGN_TX_BATCH,
+            p1=MAX_BATCH_SIZE + 1,
+            p2=P2.P2_LAST,
+            data=transactions[-1],
+        )
+    assert e.value.status == Errors.SW_BATCH_TOO_LARGE
+
+
+# Token transfers are only signed one at a time
+def test_sign_tx_batch_token_transfer(backend):
+    path: str = "m/44'/1'/0'/0/0"
+
+    transaction = Transaction(
+        nonce=1,
+        coin="USDC",
+        value=1500000,
+        to="de0b295669a9fd93d5f28d9ec85e40f4cb697bae",
+        memo="",
+        token="9fc3da866e7df3a1c57ade1a97c9f00a70f010c8",
+    ).serialize()
+
+    backend.exchange(
+        cla=CLA,
+        ins=InsType.SIGN_TX_BATCH,
+        p1=P1.P1_START,
+        p2=P2.P2_MORE,
+        data=pack_derivation_path(path),
+    )
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(
+            cla=CLA, ins=InsType.SIGN_TX_BATCH, p1=1, p2=P2.P2_LAST, data=transaction
+        )
+    assert e.value.status == Errors.SW_BATCH_TOKEN_TRANSFER
+
+
+def start_batch(backend, path: str) -> None:
+    backend.exchange(
+        cla=CLA,
+        ins=InsType.SIGN_TX_BATCH,
+        p1=P1.P1_START,
+        p2=P2.P2_MORE,
+        data=pack_derivation_path(path),
+    )
+
+
+# Sends the transactions of a batch, once its derivation path is sent
+def send_batch(backend, transactions: list) -> None:
+    for index, transaction in enumerate(transactions):
+        backend.exchange(
+            cla=CLA,
+            ins=InsType.SIGN_TX_BATCH,
+            p1=index + 1,
+            p2=P2.P2_LAST if index == len(transactions) - 1 else P2.P2_MORE,
+            data=transaction,
+        )
+
+
+# The total value of the batch is at most MAX_BATCH_TOTAL
+def test_sign_tx_batch_value_too_high(backend):
+    path: str = "m/44'/1'/0'/0/0"
+
+    transactions = [
+        Transaction(
+            nonce=nonce,
+            coin="CRAB",
+            value=value,
+            to="de0b295669a9fd93d5f28d9ec85e40f4cb697bae",
+            memo="",
+        ).serialize()
+        for nonce, value in ((1, MAX_BATCH_TOTAL), (2, 1))
+    ]
+
+    start_batch(backend, path)
+    with pytest.raises(ExceptionRAPDU) as e:
+        send_batch(backend, transactions)
+    assert e.value.status == Errors.SW_BATCH_VALUE_TOO_HIGH
+
+
+# A batch may transfer several coins: the review shows the total of each coin, then the
+# destination and the memo of each transaction
+def test_sign_tx_batch_coin_totals(backend, scenario_navigator):
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+
+    transactions = [
+        Transaction(
+            nonce=1, coin="CRAB", value=100, to=DESTINATIONS[0], memo="Rent"
+        ).serialize(),
+        Transaction(
+            nonce=2, coin="ETH", value=50, to=DESTINATIONS[1], memo="", fee=3
+        ).serialize(),
+        Transaction(
+            nonce=3, coin="CRAB", value=200, to=DESTINATIONS[2], memo="Food"
+        ).serialize(),
+    ]
+
+    with client.sign_tx_batch(path=path, transactions=transactions):
+        scenario_navigator.review_approve()
+
+    assert client.get_async_response().data == bytes([len(transactions)])
+
+    for index, transaction in enumerate(transactions):
+        rapdu = client.get_batch_signature(index)
+        _, der_sig, _ = unpack_sign_tx_response(rapdu.data)
+        assert check_signature_validity(public_key, der_sig, transaction)
+
+
+# The transactions of a batch have distinct nonces
+def test_sign_tx_batch_duplicate_nonce(backend):
+    path: str = "m/44'/1'/0'/0/0"
+
+    transactions = batch_transactions(2) + [
+        Transaction(
+            nonce=1,
+            coin="CRAB",
+            value=300,
+            to="9fc3da866e7df3a1c57ade1a97c9f00a70f010c8",
+            memo="",
+        ).serialize()
+    ]
+
+    start_batch(backend, path)
+    with pytest.raises(ExceptionRAPDU) as e:
+        send_batch(backend, transactions)
+    assert e.value.status == Errors.SW_BATCH_DUPLICATE_NONCE
+
+
+# Transactions must follow the derivation path, in sequence
+def test_sign_tx_batch_out_of_sequence(backend):
+    path: str = "m/44'/1'/0'/0/0"
+    transactions = batch_transactions(2)
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(
+            cla=CLA, ins=InsType.SIGN_TX_BATCH, p1=1, p2=P2.P2_LAST, data=transactions[0]
+        )
+    assert e.value.status == Errors.SW_BAD_STATE
+
+    backend.exchange(
+        cla=CLA,
+        ins=InsType.SIGN_TX_BATCH,
+        p1=P1.P1_START,
+        p2=P2.P2_MORE,
+        data=pack_derivation_path(path),
+    )
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(
+            cla=CLA, ins=InsType.SIGN_TX_BATCH, p1=2, p2=P2.P2_LAST, data=transactions[1]
+        )
+    assert e.value.status == Errors.SW_BAD_STATE
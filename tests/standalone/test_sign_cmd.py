@@ -4,17 +4,22 @@ from application_client.boilerplate_transaction import Transaction
 from application_client.boilerplate_command_sender import (
     BoilerplateCommandSender,
     Errors,
+    RiskFlags,
 )
 from application_client.boilerplate_response_unpacker import (
     unpack_get_public_key_response,
     unpack_sign_tx_response,
+    unpack_sign_tx_response_with_hash,
+    unpack_sign_tx_response_with_risk,
 )
 from application_client.utils import check_signature_validity
 from ragger.error import ExceptionRAPDU
-from ragger.navigator import NavIns, NavInsID
+from ragger.navigator import NavInsID
+from sha3 import keccak_256
 
-# In these tests we check the behavior of the device when asked to sign a transaction
+from .utils import Setting, toggle_setting
 
+# In these tests we check the behavior of the device when asked to sign a transaction
 
 # In this test a transaction is sent to the device to be signed and validated on screen.
 # The transaction is short and will be sent in one chunk.
@@ -40,15 +45,7 @@ def test_sign_tx_short_tx(backend, scenario_navigator, device, navigator):
 
     # Enable display of transaction memo (NBGL devices only)
     if not device.is_nano:
-        navigator.navigate(
-            [
-                NavInsID.USE_CASE_HOME_SETTINGS,
-                NavIns(NavInsID.TOUCH, (200, 113)),
-                NavInsID.USE_CASE_SUB_SETTINGS_EXIT,
-            ],
-            screen_change_before_first_instruction=False,
-            screen_change_after_last_instruction=False,
-        )
+        toggle_setting(device, navigator, Setting.DISPLAY_MEMO)
 
     # Send the sign device instruction.
     # As it requires on-screen validation, the function is asynchronous.
@@ -103,6 +100,36 @@ def test_sign_tx_short_tx_no_memo(backend, scenario_navigator, device):
     assert check_signature_validity(public_key, der_sig, transaction)
 
 
+# The transaction hash computed by the device is requested with the signature.
+def test_sign_tx_return_hash(backend, scenario_navigator, device):
+    if device.is_nano:
+        pytest.skip("Skipping this test for Nano devices")
+
+    # Use the app interface instead of raw interface
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+
+    transaction = Transaction(
+        nonce=1,
+        coin="CRAB",
+        value=777,
+        to="de0b295669a9fd93d5f28d9ec85e40f4cb697bae",
+        memo="For u EthDev",
+    ).serialize()
+
+    with client.sign_tx(path=path, transaction=transaction, return_hash=True):
+        scenario_navigator.review_approve()
+
+    # The hash is the Keccak256 of the raw transaction, which is the signed message
+    response = client.get_async_response().data
+    _, der_sig, _, tx_hash = unpack_sign_tx_response_with_hash(response)
+    assert tx_hash == keccak_256(transaction).digest()
+    assert check_signature_validity(public_key, der_sig, transaction)
+
+
 # In this test a transaction is sent to the device to be signed and validated on screen.
 # This test is mostly the same as the previous one but with different values.
 # In particular the long memo will force the transaction to be sent in multiple chunks
@@ -130,15 +157,7 @@ def test_sign_tx_long_tx(backend, scenario_navigator, device, navigator):
 
     # Enable display of transaction memo (NBGL devices only)
     if not device.is_nano:
-        navigator.navigate(
-            [
-                NavInsID.USE_CASE_HOME_SETTINGS,
-                NavIns(NavInsID.TOUCH, (200, 113)),
-                NavInsID.USE_CASE_SUB_SETTINGS_EXIT,
-            ],
-            screen_change_before_first_instruction=False,
-            screen_change_after_last_instruction=False,
-        )
+        toggle_setting(device, navigator, Setting.DISPLAY_MEMO)
 
     # Send the sign device instruction.
     # As it requires on-screen validation, the function is asynchronous.
@@ -177,3 +196,150 @@ def test_sign_tx_refused(backend, scenario_navigator):
     # Assert that we have received a refusal
     assert e.value.status == Errors.SW_DENY
     assert len(e.value.data) == 0
+
+
+# Transaction whose coin ticker is too long to be displayed: it is refused before any review.
+def test_sign_tx_coin_too_long(backend):
+    # Use the app interface instead of raw interface
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    transaction = Transaction(
+        nonce=1,
+        coin="CRABCRABCRAB",
+        value=666,
+        to="de0b295669a9fd93d5f28d9ec85e40f4cb697bae",
+        memo="",
+    ).serialize()
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        with client.sign_tx(path=path, transaction=transaction):
+            pass
+
+    # Assert that we have received a display error
+    assert e.value.status == Errors.SW_DISPLAY_AMOUNT_FAIL
+    assert len(e.value.data) == 0
+
+
+# Transaction of a zero value to the address of the signing key. The device shows a warning
+# for each risk before the review, and returns the risk flags with the signature.
+def test_sign_tx_risk_flags(
+    backend, scenario_navigator, device, navigator, test_name, default_screenshot_path
+):
+    if device.is_nano:
+        pytest.skip("Skipping this test for Nano devices")
+
+    # Use the app interface instead of raw interface
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+    own_address = keccak_256(public_key[1:]).digest()[-20:].hex()
+
+    transaction = Transaction(
+        nonce=1,
+        coin="CRAB",
+        value=0,
+        to=own_address,
+        memo="",
+    ).serialize()
+
+    with client.sign_tx(path=path, transaction=transaction, return_risk=True):
+        # Continue after the self transfer and the zero value warnings
+        navigator.navigate_and_compare(
+            default_screenshot_path,
+            test_name + "_warnings",
+            [NavInsID.USE_CASE_CHOICE_CONFIRM, NavInsID.USE_CASE_CHOICE_CONFIRM],
+            screen_change_before_first_instruction=False,
+        )
+        scenario_navigator.review_approve()
+
+    response = client.get_async_response().data
+    _, der_sig, _, risk_flags = unpack_sign_tx_response_with_risk(response)
+    assert check_signature_validity(public_key, der_sig, transaction)
+    assert risk_flags == RiskFlags.SELF_TRANSFER | RiskFlags.ZERO_VALUE
+
+
+# Transaction whose memo is longer than the cap of the app (MAX_MEMO_LEN, 400 bytes).
+# It is refused when parsed, before any review, whatever the blind signing setting.
+def test_sign_tx_memo_too_long(backend):
+    # Use the app interface instead of raw interface
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    transaction = Transaction(
+        nonce=1,
+        coin="CRAB",
+        value=666,
+        to="de0b295669a9fd93d5f28d9ec85e40f4cb697bae",
+        memo="m" * 401,
+    ).serialize()
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        with client.sign_tx(path=path, transaction=transaction):
+            pass
+
+    # Assert that we have received a memo length error
+    assert e.value.status == Errors.SW_MEMO_TOO_LONG
+    assert len(e.value.data) == 0
+
+
+# Transaction that the device cannot decode, while blind signing is disabled in the app settings.
+# The device explains on screen why the transaction is refused and a parsing error is returned.
+def test_sign_tx_blind_signing_disabled(backend, navigator):
+    # Use the app interface instead of raw interface
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    transaction = b'{"contract":"de0b295669a9fd93d5f28d9ec85e40f4cb697bae","data":"a9059cbb"}'
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        with client.sign_tx(path=path, transaction=transaction):
+            navigator.navigate(
+                [NavInsID.USE_CASE_STATUS_DISMISS],
+                screen_change_after_last_instruction=False,
+            )
+
+    # Assert that we have received a parsing error
+    assert e.value.status == Errors.SW_TX_PARSING_FAIL
+    assert len(e.value.data) == 0
+
+
+# Same transaction, once blind signing is enabled in the app settings. The device shows the NBGL
+# blind signing warning, then the hash and the path of the transaction, and signs its hash.
+def test_sign_tx_blind_signing_enabled(
+    backend, scenario_navigator, device, navigator, test_name, default_screenshot_path
+):
+    if device.is_nano:
+        pytest.skip("Skipping this test for Nano devices")
+
+    # Use the app interface instead of raw interface
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+
+    transaction = b'{"contract":"de0b295669a9fd93d5f28d9ec85e40f4cb697bae","data":"a9059cbb"}'
+
+    # Enable blind signing, the snapshots show the switch once toggled
+    toggle_setting(
+        device,
+        navigator,
+        Setting.BLIND_SIGNING,
+        default_screenshot_path,
+        test_name + "_settings",
+    )
+
+    with client.sign_tx(path=path, transaction=transaction):
+        # Accept the risk on the blind signing warning, then approve the review
+        navigator.navigate(
+            [NavInsID.USE_CASE_CHOICE_REJECT],
+            screen_change_before_first_instruction=False,
+        )
+        scenario_navigator.review_approve()
+
+    response = client.get_async_response().data
+    _, der_sig, _ = unpack_sign_tx_response(response)
+    assert check_signature_validity(public_key, der_sig, transaction)
@@ -0,0 +1,75 @@
+import pytest
+
+from application_client.boilerplate_command_sender import (
+    BoilerplateCommandSender,
+    CLA,
+    InsType,
+    P1,
+    P2,
+    Errors,
+    pack_spending_policy,
+)
+from ragger.error import ExceptionRAPDU
+
+# In these tests we check the behavior of the device when asked to set spending limits
+
+
+# The new limits are displayed on the device and accepted by the user
+def test_set_spending_policy_accepted(backend, scenario_navigator):
+    client = BoilerplateCommandSender(backend)
+
+    with client.set_spending_policy(
+        max_per_tx=1000, cumulative_limit=5000, refuse=True
+    ):
+        scenario_navigator.review_approve()
+
+    assert client.get_async_response().status == 0x9000
+
+
+# The optional high value threshold is displayed with the limits
+def test_set_spending_policy_high_value_threshold(backend, scenario_navigator):
+    client = BoilerplateCommandSender(backend)
+
+    with client.set_spending_policy(
+        max_per_tx=1000, cumulative_limit=0, refuse=False, high_value_threshold=500
+    ):
+        scenario_navigator.review_approve()
+
+    assert client.get_async_response().status == 0x9000
+
+
+# The new limits are refused by the user
+def test_set_spending_policy_refused(backend, scenario_navigator):
+    client = BoilerplateCommandSender(backend)
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        with client.set_spending_policy(
+            max_per_tx=1000, cumulative_limit=0, refuse=False
+        ):
+            scenario_navigator.review_reject()
+
+    assert e.value.status == Errors.SW_DENY
+    assert len(e.value.data) == 0
+
+
+# Ensure the app rejects malformed policies without displaying anything
+def test_set_spending_policy_malformed(backend):
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(
+            cla=CLA,
+            ins=InsType.SET_SPENDING_POLICY,
+            p1=P1.P1_START,
+            p2=P2.P2_LAST,
+            data=pack_spending_policy(1000, 0, False)[:-1],
+        )
+    assert e.value.status == Errors.SW_WRONG_APDU_LENGTH
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        backend.exchange(
+            cla=CLA,
+            ins=InsType.SET_SPENDING_POLICY,
+            p1=P1.P1_START,
+            p2=P2.P2_LAST,
+            data=pack_spending_policy(1000, 0, False)[:-1] + b"\x02",
+        )
+    assert e.value.status == Errors.SW_POLICY_PARSING_FAIL
@@ -0,0 +1,115 @@
+import pytest
+
+from application_client.boilerplate_command_sender import (
+    BoilerplateCommandSender,
+    Errors,
+    TxField,
+)
+from application_client.boilerplate_descriptor import pack_token_info
+from application_client.boilerplate_response_unpacker import (
+    unpack_get_public_key_response,
+    unpack_parse_tx_response,
+    unpack_sign_tx_response,
+)
+from application_client.boilerplate_transaction import Transaction
+from application_client.utils import check_signature_validity
+from ragger.error import ExceptionRAPDU
+
+# In these tests we check the behavior of the device when provided with token descriptors
+
+TOKEN_ADDRESS: str = "9fc3da866e7df3a1c57ade1a97c9f00a70f010c8"
+
+
+# The amount of a verified token transfer is displayed with the verified ticker and decimals
+def test_sign_tx_verified_token(backend, scenario_navigator):
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+
+    rapdu = client.provide_token_info(pack_token_info(TOKEN_ADDRESS, "USDC", 6))
+    assert rapdu.status == 0x9000
+
+    transaction = Transaction(
+        nonce=1,
+        coin="FAKE",
+        value=1500000,
+        to="de0b295669a9fd93d5f28d9ec85e40f4cb697bae",
+        memo="",
+        token=TOKEN_ADDRESS,
+    ).serialize()
+
+    with client.sign_tx(path=path, transaction=transaction):
+        scenario_navigator.review_approve()
+
+    response = client.get_async_response().data
+    _, der_sig, _ = unpack_sign_tx_response(response)
+    assert check_signature_validity(public_key, der_sig, transaction)
+
+
+# A verified token transfer with fees is reviewed with more fields than a plain transfer:
+# amount, fees, token, destination and signing account
+def test_sign_tx_verified_token_with_fees(backend, scenario_navigator):
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+
+    rapdu = client.provide_token_info(pack_token_info(TOKEN_ADDRESS, "USDC", 6))
+    assert rapdu.status == 0x9000
+
+    transaction = Transaction(
+        nonce=1,
+        coin="CRAB",
+        value=1500000,
+        to="de0b295669a9fd93d5f28d9ec85e40f4cb697bae",
+        memo="",
+        token=TOKEN_ADDRESS,
+        fee=21,
+    ).serialize()
+
+    rapdu = client.parse_tx(path=path, transaction=transaction)
+    assert unpack_parse_tx_response(rapdu.data) == [
+        (TxField.AMOUNT, "USDC 1.5"),
+        (TxField.FEES, "CRAB 21"),
+        (TxField.TOKEN, "0x" + TOKEN_ADDRESS.upper()),
+        (TxField.DESTINATION, "0xDE0B295669A9FD93D5F28D9EC85E40F4CB697BAE"),
+        (TxField.FROM, "Account 0"),
+    ]
+
+    with client.sign_tx(path=path, transaction=transaction):
+        scenario_navigator.review_approve()
+
+    response = client.get_async_response().data
+    _, der_sig, _ = unpack_sign_tx_response(response)
+    assert check_signature_validity(public_key, der_sig, transaction)
+
+
+# Descriptors not signed by the trusted key are rejected
+def test_token_info_wrong_signature(backend):
+    client = BoilerplateCommandSender(backend)
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.provide_token_info(
+            pack_token_info(TOKEN_ADDRESS, "USDC", 6, key=bytes.fromhex("01" * 32))
+        )
+    assert e.value.status == Errors.SW_TOKEN_INFO_SIGNATURE_FAIL
+
+
+# Descriptors for another chain, or with invalid fields, are rejected
+def test_token_info_invalid(backend):
+    client = BoilerplateCommandSender(backend)
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.provide_token_info(pack_token_info(TOKEN_ADDRESS, "USDC", 6, chain_id=42))
+    assert e.value.status == Errors.SW_TOKEN_INFO_INVALID
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.provide_token_info(pack_token_info(TOKEN_ADDRESS, "US DC", 6))
+    assert e.value.status == Errors.SW_TOKEN_INFO_INVALID
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.provide_token_info(pack_token_info(TOKEN_ADDRESS, "USDC", 6)[:-1])
+    assert e.value.status == Errors.SW_WRONG_APDU_LENGTH
@@ -0,0 +1,85 @@
+import pytest
+
+from application_client.boilerplate_command_sender import (
+    BoilerplateCommandSender,
+    Errors,
+)
+from application_client.boilerplate_descriptor import (
+    DESCRIPTOR_TYPE_TOKEN,
+    pack_trusted_name,
+    sign_descriptor,
+)
+from application_client.boilerplate_response_unpacker import (
+    unpack_get_public_key_response,
+    unpack_sign_tx_response,
+)
+from application_client.boilerplate_transaction import Transaction
+from application_client.utils import check_signature_validity
+from ragger.error import ExceptionRAPDU
+
+# In these tests we check the behavior of the device when provided with trusted names
+
+ADDRESS: str = "de0b295669a9fd93d5f28d9ec85e40f4cb697bae"
+
+
+# The destination of the transaction is displayed with its verified name
+def test_sign_tx_with_trusted_name(backend, scenario_navigator):
+    client = BoilerplateCommandSender(backend)
+    path: str = "m/44'/1'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+
+    rapdu = client.provide_trusted_name(pack_trusted_name(ADDRESS, "crab.eth"))
+    assert rapdu.status == 0x9000
+
+    transaction = Transaction(
+        nonce=1,
+        coin="CRAB",
+        value=777,
+        to=ADDRESS,
+        memo="For u EthDev",
+    ).serialize()
+
+    with client.sign_tx(path=path, transaction=transaction):
+        scenario_navigator.review_approve()
+
+    response = client.get_async_response().data
+    _, der_sig, _ = unpack_sign_tx_response(response)
+    assert check_signature_validity(public_key, der_sig, transaction)
+
+
+# Descriptors not signed by the trusted key are rejected
+def test_trusted_name_wrong_signature(backend):
+    client = BoilerplateCommandSender(backend)
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.provide_trusted_name(
+            pack_trusted_name(ADDRESS, "crab.eth", key=bytes.fromhex("01" * 32))
+        )
+    assert e.value.status == Errors.SW_TRUSTED_NAME_SIGNATURE_FAIL
+
+
+# A descriptor signed as another kind of descriptor is rejected
+def test_trusted_name_wrong_descriptor_type(backend):
+    client = BoilerplateCommandSender(backend)
+
+    name_raw = "crab.eth".encode("ascii")
+    payload = bytes.fromhex(ADDRESS) + len(name_raw).to_bytes(1, "big") + name_raw
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.provide_trusted_name(sign_descriptor(DESCRIPTOR_TYPE_TOKEN, payload))
+    assert e.value.status == Errors.SW_TRUSTED_NAME_SIGNATURE_FAIL
+
+
+# Malformed descriptors are rejected
+def test_trusted_name_malformed(backend):
+    client = BoilerplateCommandSender(backend)
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.provide_trusted_name(pack_trusted_name(ADDRESS, "crab.eth")[:-1])
+    assert e.value.status == Errors.SW_WRONG_APDU_LENGTH
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.provide_trusted_name(pack_trusted_name(ADDRESS, "crab\n.eth"))
+    assert e.value.status == Errors.SW_TRUSTED_NAME_INVALID
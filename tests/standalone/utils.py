@@ -0,0 +1,63 @@
+from enum import IntEnum
+from pathlib import Path
+from typing import Optional
+
+from ragger.navigator import NavIns, NavInsID, Navigator
+
+# Local helpers of the standalone tests
+
+
+# Switches of the settings page, in display order (SETTING_* in src/settings.rs)
+class Setting(IntEnum):
+    DISPLAY_MEMO = 0
+    BLIND_SIGNING = 1
+    EXPERT_MODE = 2
+    REPLAY_GUARD = 3
+    SPENDING_LIMITS = 4
+    ADDRESS_BOOK = 5
+
+
+# Layout of the switches on the settings pages of each touch device: abscissa of the switches,
+# ordinate of the first switch of a page, distance between two switches and number of switches
+# per page.
+SWITCH_LAYOUTS = {
+    "stax": (200, 113, 148, 3),
+    "flex": (240, 120, 150, 3),
+    "apex_p": (150, 80, 100, 3),
+}
+
+
+def toggle_setting(
+    device,
+    navigator: Navigator,
+    setting: Setting,
+    default_screenshot_path: Optional[Path] = None,
+    test_name: Optional[str] = None,
+) -> None:
+    """Toggles a switch of the settings page of a touch device, then goes back home.
+
+    If a test name is given, the screens are compared with its snapshots, which shows the state
+    of the switch once toggled.
+    """
+    x, first_y, step, per_page = SWITCH_LAYOUTS[device.name]
+    page, row = divmod(int(setting), per_page)
+    instructions = [NavInsID.USE_CASE_HOME_SETTINGS]
+    instructions += [NavInsID.USE_CASE_SUB_SETTINGS_NEXT] * page
+    instructions += [
+        NavIns(NavInsID.TOUCH, (x, first_y + row * step)),
+        NavInsID.USE_CASE_SUB_SETTINGS_EXIT,
+    ]
+    if test_name is None:
+        navigator.navigate(
+            instructions,
+            screen_change_before_first_instruction=False,
+            screen_change_after_last_instruction=False,
+        )
+    else:
+        navigator.navigate_and_compare(
+            default_screenshot_path,
+            test_name,
+            instructions,
+            screen_change_before_first_instruction=False,
+            screen_change_after_last_instruction=False,
+        )
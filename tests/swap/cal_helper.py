@@ -36,10 +36,6 @@ BOL_DYNAMIC_USDT_CURRENCY_CONFIGURATION = CurrencyConfiguration(
     packed_derivation_path=BOL_DYNAMIC_USDT_PACKED_DERIVATION_PATH,
 )
 
-# Token addresses
-TOKEN_DYNAMIC_USDT_ADDRESS = (
-    "cafebabedeadbeefcafebabedeadbeefcafebabedeadbeefcafebabedeadbeef"
-)
-TOKEN_HARDCODED_USDC_ADDRESS = (
-    "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef"
-)
+# Contract address of the USDT token, verified by the application with a token descriptor
+# provided during the swap session
+TOKEN_DYNAMIC_USDT_ADDRESS = "cafebabedeadbeefcafebabedeadbeefcafebabe"
@@ -1,7 +1,8 @@
 import pytest
+from sha3 import keccak_256
 from ledger_app_clients.exchange.test_runner import (
     ExchangeTestRunner,
-    ALL_TESTS_EXCEPT_MEMO_THORSWAP_AND_FEES,
+    ALL_TESTS,
 )
 
 from application_client.boilerplate_currency_utils import BOL_PATH
@@ -9,22 +10,17 @@ from application_client.boilerplate_command_sender import (
     BoilerplateCommandSender,
     Errors as BoilerplateErrors,
 )
+from application_client.boilerplate_descriptor import pack_token_info
 from application_client.boilerplate_transaction import Transaction
 
 from . import cal_helper as cal
 
 
-# Token addresses from the hardcoded database in src/token/token_db.c
-# USDC token with 12 decimals
-TOKEN_USDC_ADDRESS = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef"
-TOKEN_LINK_ADDRESS = "ffeeddccbbaa99887766554433221100ffeeddccbbaa99887766554433221100"
-UNKNOWN_TOKEN_ADDRESS = (
-    "00ffeeddccbbaa99887766554433221100ffeeddccbbaa998877665544332211"
-)
+# Contract address of the USDT token of the token swap tests
+TOKEN_USDT_ADDRESS = cal.TOKEN_DYNAMIC_USDT_ADDRESS
 
-# CAL dynamic token address (not in hardcoded database, will be provided dynamically)
-TOKEN_DYNAMIC_USDT_ADDRESS = cal.TOKEN_DYNAMIC_USDT_ADDRESS
-TOKEN_HARDCODED_USDC_ADDRESS = cal.TOKEN_HARDCODED_USDC_ADDRESS
+# Thorswap and LiFi encode the swap parameters in the memo, which this coin does not support.
+TESTS = [t for t in ALL_TESTS if "thorswap" not in t and "lifi" not in t]
 
 
 # ExchangeTestRunner implementation for Boilerplate.
@@ -37,11 +33,11 @@ class BoilerplateTests(ExchangeTestRunner):
     # A valid template address of a supposed trade partner.
     valid_destination_1 = "de0b295669a9fd93d5f28d9ec85e40f4cb697bae"
     # A memo to use associated with the destination address if applicable.
-    valid_destination_memo_1 = ""
+    valid_destination_memo_1 = "Boilerplate swap 1"
     # A second valid template address of a supposed trade partner.
     valid_destination_2 = "9fc3da866e7dF3a1c57ade1a97c9f00a70f010c8"
     # A second memo to use associated with the destination address if applicable.
-    valid_destination_memo_2 = ""
+    valid_destination_memo_2 = "Boilerplate swap 2"
     # The address of the Speculos seed on the BOL_PATH.
     valid_refund = "fd2095a37e72be2cd575d18fe8f16e78c51eafa3"
     valid_refund_memo = ""
@@ -49,8 +45,8 @@ class BoilerplateTests(ExchangeTestRunner):
     # Values we ask the ExchangeTestRunner to use in the test setup
     valid_send_amount_1 = 1000
     valid_send_amount_2 = 666
-    valid_fees_1 = 0
-    valid_fees_2 = 0
+    valid_fees_1 = 100
+    valid_fees_2 = 42
 
     # Fake addresses to test the address rejection code.
     fake_refund = "abcdabcd"
@@ -62,13 +58,17 @@ class BoilerplateTests(ExchangeTestRunner):
     signature_refusal_error_code = BoilerplateErrors.SW_DENY
     wrong_amount_error_code = BoilerplateErrors.SW_SWAP_FAIL
     wrong_destination_error_code = BoilerplateErrors.SW_SWAP_FAIL
+    wrong_memo_error_code = BoilerplateErrors.SW_SWAP_FAIL
+    wrong_fees_error_code = BoilerplateErrors.SW_SWAP_FAIL
 
     # The final transaction to craft and send as part of the SWAP finalization.
     # This function will be called by the ExchangeTestRunner in a callback like way
     def perform_final_tx(self, destination, send_amount, fees, memo):
-        # Create the transaction that will be sent to the device for signing
+        # Create the transaction that will be sent to the device for signing. The destination
+        # may be given by the provider with a 0x prefix and in checksum case.
+        to = destination.lower().removeprefix("0x")
         tx = Transaction(
-            nonce=1, coin="CRAB", to=destination, value=send_amount, memo=memo
+            nonce=1, coin="CRAB", to=to, value=send_amount, memo=memo, fee=fees
         ).serialize()
 
         # Send the TX
@@ -79,10 +79,107 @@ class BoilerplateTests(ExchangeTestRunner):
         # TODO : assert signature validity. Not required but recommended
 
 
+def to_checksum_address(address: str) -> str:
+    # EIP-55 mixed case encoding of a hex address
+    digest = keccak_256(address.lower().encode()).hexdigest()
+    return "".join(
+        c.upper() if int(digest[i], 16) >= 8 else c for i, c in enumerate(address.lower())
+    )
+
+
+# Same setup, with the refund address sent in EIP-55 checksum case and with a 0x prefix.
+# The application must still recognize it as its own address.
+class BoilerplateChecksumRefundTests(BoilerplateTests):
+    valid_refund = "0x" + to_checksum_address(BoilerplateTests.valid_refund)
+
+
+# Same setup, swapping the USDT token. Its descriptor is provided during the swap session, so
+# that the application can check the token of the transaction against the coin configuration.
+class BoilerplateTokenTests(BoilerplateTests):
+    currency_configuration = cal.BOL_DYNAMIC_USDT_CURRENCY_CONFIGURATION
+
+    def perform_final_tx(self, destination, send_amount, fees, memo):
+        client = BoilerplateCommandSender(self.backend)
+        client.provide_token_info(pack_token_info(TOKEN_USDT_ADDRESS, "USDT", 6))
+
+        to = destination.lower().removeprefix("0x")
+        tx = Transaction(
+            nonce=1,
+            coin="USDT",
+            to=to,
+            value=send_amount,
+            memo=memo,
+            fee=fees,
+            token=TOKEN_USDT_ADDRESS,
+        ).serialize()
+        client.sign_tx_sync(path=BOL_PATH, transaction=tx)
+
+
+# Same setup, with the provider accounts of a SELL or a FUND sent in EIP-55 checksum case and
+# with a 0x prefix. They are not checked by CHECK_ADDRESS: the application decodes them, so that
+# the prefix and the case do not matter, and compares the address bytes with the transaction.
+class BoilerplateProviderAccountTests(BoilerplateTests):
+    valid_destination_1 = "0x" + to_checksum_address(BoilerplateTests.valid_destination_1)
+    valid_destination_2 = "0x" + to_checksum_address(BoilerplateTests.valid_destination_2)
+
+
+# The ExchangeTestRunner names its tests after the Exchange sub-flow they exercise
+SWAP_TESTS = [t for t in TESTS if t.startswith("swap")]
+SELL_TESTS = [t for t in TESTS if t.startswith("sell")]
+FUND_TESTS = [t for t in TESTS if t.startswith("fund")]
+OTHER_TESTS = [t for t in TESTS if t not in SWAP_TESTS + SELL_TESTS + FUND_TESTS]
+
+
 # We use a class to reuse the same Speculos instance (faster performances)
+# Here we don't test Thorswap / LiFi
 class TestsBoilerplate:
-    # Run all the tests applicable to our setup: here we don't test fees mismatch, memo mismatch, and Thorswap / LiFi
-    @pytest.mark.parametrize("test_to_run", ALL_TESTS_EXCEPT_MEMO_THORSWAP_AND_FEES)
+    # SWAP: the payin transaction is sent to the swap provider, the refund address is checked
+    @pytest.mark.parametrize("test_to_run", SWAP_TESTS)
+    def test_boilerplate_swap(self, backend, exchange_navigation_helper, test_to_run):
+        BoilerplateTests(backend, exchange_navigation_helper).run_test(test_to_run)
+
+    # SELL: the payin transaction is sent to the account of the provider
+    @pytest.mark.parametrize("test_to_run", SELL_TESTS)
+    def test_boilerplate_sell(self, backend, exchange_navigation_helper, test_to_run):
+        BoilerplateTests(backend, exchange_navigation_helper).run_test(test_to_run)
+
+    # FUND: the payin transaction funds an account held by the provider
+    @pytest.mark.parametrize("test_to_run", FUND_TESTS)
+    def test_boilerplate_fund(self, backend, exchange_navigation_helper, test_to_run):
+        BoilerplateTests(backend, exchange_navigation_helper).run_test(test_to_run)
+
+    # SWAP with a refund address that is not in lowercase hex
+    @pytest.mark.parametrize(
+        "test_to_run", [t for t in SWAP_TESTS if t.startswith("swap_valid")]
+    )
+    def test_boilerplate_swap_checksum_refund(
+        self, backend, exchange_navigation_helper, test_to_run
+    ):
+        BoilerplateChecksumRefundTests(backend, exchange_navigation_helper).run_test(
+            test_to_run
+        )
+
+    # SELL and FUND with a provider account that is not in lowercase hex
+    @pytest.mark.parametrize(
+        "test_to_run",
+        [t for t in SELL_TESTS + FUND_TESTS if "_valid" in t or "wrong_destination" in t],
+    )
+    def test_boilerplate_provider_account(
+        self, backend, exchange_navigation_helper, test_to_run
+    ):
+        BoilerplateProviderAccountTests(backend, exchange_navigation_helper).run_test(
+            test_to_run
+        )
+
+    # SWAP of a token, verified with its descriptor
+    @pytest.mark.parametrize(
+        "test_to_run", [t for t in SWAP_TESTS if t.startswith("swap_valid")]
+    )
+    def test_boilerplate_swap_token(self, backend, exchange_navigation_helper, test_to_run):
+        BoilerplateTokenTests(backend, exchange_navigation_helper).run_test(test_to_run)
+
+    # Tests that are not specific to a sub-flow
+    @pytest.mark.parametrize("test_to_run", OTHER_TESTS)
     def test_boilerplate(self, backend, exchange_navigation_helper, test_to_run):
         # Call run_test method of ExchangeTestRunner
         BoilerplateTests(backend, exchange_navigation_helper).run_test(test_to_run)
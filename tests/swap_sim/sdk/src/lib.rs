@@ -0,0 +1,423 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust - Swap simulation.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+//! Host implementation of the subset of `ledger_device_sdk` used by the swap feature.
+//!
+//! It only provides what is needed to compile and run the parsers and the swap validation of
+//! the application on the host:
+//! - NVM storage is plain memory,
+//! - Keccak256 is computed in software,
+//! - ECDSA signatures are verified in software, with the `k256` crate,
+//! - key derivation is simulated: the public key of a path is a deterministic function of the
+//!   path, it is NOT the key derived from a seed,
+//! - the `os_lib_call` entry points, which only exist on a device, panic.
+
+#![no_std]
+
+extern crate alloc;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log {
+    ($($arg:tt)*) => {{
+        let _ = format_args!($($arg)*);
+    }};
+}
+
+/// Logs are discarded on the host.
+pub mod log {
+    pub use crate::{
+        __log as debug, __log as error, __log as info, __log as trace, __log as warn,
+    };
+}
+
+/// Persistent data, kept in memory.
+pub struct NVMData<T> {
+    data: T,
+}
+
+impl<T> NVMData<T> {
+    pub const fn new(data: T) -> NVMData<T> {
+        NVMData { data }
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.data
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+}
+
+pub mod nvm {
+    /// Storage updated in a single write.
+    pub struct AtomicStorage<T> {
+        value: T,
+    }
+
+    impl<T: Copy> AtomicStorage<T> {
+        pub const fn new(value: &T) -> AtomicStorage<T> {
+            AtomicStorage { value: *value }
+        }
+
+        pub fn get_ref(&self) -> &T {
+            &self.value
+        }
+
+        /// # Safety
+        ///
+        /// Unsafe on a device, where it writes to flash. Always safe on the host.
+        pub unsafe fn update(&mut self, value: &T) {
+            self.value = *value;
+        }
+    }
+}
+
+pub mod hash {
+    #[derive(Debug)]
+    pub struct HashError;
+
+    pub trait HashInit: Sized {
+        fn new() -> Self;
+        fn update(&mut self, input: &[u8]) -> Result<(), HashError>;
+        fn finalize(&mut self, output: &mut [u8]) -> Result<(), HashError>;
+
+        fn hash(&mut self, input: &[u8], output: &mut [u8]) -> Result<(), HashError> {
+            self.update(input)?;
+            self.finalize(output)
+        }
+    }
+
+    pub mod sha3 {
+        use super::{HashError, HashInit};
+        use sha3::Digest;
+
+        pub struct Keccak256(sha3::Keccak256);
+
+        impl HashInit for Keccak256 {
+            fn new() -> Self {
+                Keccak256(sha3::Keccak256::new())
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), HashError> {
+                self.0.update(input);
+                Ok(())
+            }
+
+            fn finalize(&mut self, output: &mut [u8]) -> Result<(), HashError> {
+                let digest = self.0.finalize_reset();
+                output
+                    .get_mut(..digest.len())
+                    .ok_or(HashError)?
+                    .copy_from_slice(&digest);
+                Ok(())
+            }
+        }
+    }
+}
+
+pub mod ecc {
+    use crate::hash::{sha3::Keccak256, HashInit};
+    use k256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey};
+
+    #[derive(Clone, Copy, Debug)]
+    pub enum CurvesId {
+        Secp256k1,
+    }
+
+    #[derive(Debug)]
+    pub struct CxError;
+
+    pub struct ChainCode {
+        pub value: [u8; 32],
+    }
+
+    pub struct ECPublicKey<const S: usize, const TY: char> {
+        pub pubkey: [u8; S],
+        pub curve: CurvesId,
+    }
+
+    impl<const S: usize, const TY: char> ECPublicKey<S, TY> {
+        pub fn new(curve: CurvesId) -> ECPublicKey<S, TY> {
+            ECPublicKey {
+                pubkey: [0u8; S],
+                curve,
+            }
+        }
+
+        /// Verifies a DER-encoded ECDSA signature of `hash`. As on a device, signatures with a
+        /// high S value are accepted.
+        pub fn verify(&self, signature: (&[u8], u32), hash: &[u8]) -> bool {
+            let Some(der) = signature.0.get(..signature.1 as usize) else {
+                return false;
+            };
+            let (Ok(key), Ok(signature)) = (
+                VerifyingKey::from_sec1_bytes(&self.pubkey),
+                Signature::from_der(der),
+            ) else {
+                return false;
+            };
+            let signature = signature.normalize_s().unwrap_or(signature);
+            key.verify_prehash(hash, &signature).is_ok()
+        }
+    }
+
+    /// Simulated private key, identified by its derivation path.
+    pub struct ECPrivateKey {
+        path_hash: [u8; 32],
+    }
+
+    impl ECPrivateKey {
+        /// Returns the simulated public key: `0x04 || keccak(path) || keccak(keccak(path))`.
+        pub fn public_key(&self) -> Result<ECPublicKey<65, 'W'>, CxError> {
+            let mut key = ECPublicKey::new(CurvesId::Secp256k1);
+            key.pubkey[0] = 0x04;
+            key.pubkey[1..33].copy_from_slice(&self.path_hash);
+            Keccak256::new()
+                .hash(&self.path_hash, &mut key.pubkey[33..])
+                .map_err(|_| CxError)?;
+            Ok(key)
+        }
+    }
+
+    pub trait SeedDerive {
+        type Target;
+        fn derive_from(path: &[u32]) -> (Self::Target, Option<ChainCode>);
+    }
+
+    pub struct Secp256k1;
+
+    impl SeedDerive for Secp256k1 {
+        type Target = ECPrivateKey;
+
+        fn derive_from(path: &[u32]) -> (ECPrivateKey, Option<ChainCode>) {
+            let mut keccak256 = Keccak256::new();
+            let mut path_hash = [0u8; 32];
+            for component in path {
+                let _ = keccak256.update(&component.to_be_bytes());
+            }
+            let _ = keccak256.finalize(&mut path_hash);
+            (ECPrivateKey { path_hash }, None)
+        }
+    }
+}
+
+pub mod io {
+    /// Header of an APDU.
+    #[derive(Clone, Copy, Debug)]
+    pub struct ApduHeader {
+        pub cla: u8,
+        pub ins: u8,
+        pub p1: u8,
+        pub p2: u8,
+    }
+}
+
+pub mod libcall {
+    /// Application-specific part of a swap error code.
+    pub trait SwapAppErrorCodeTrait: Copy {
+        fn as_u8(self) -> u8;
+    }
+
+    pub enum LibCallCommand {
+        SwapSignTransaction,
+        SwapGetPrintableAmount,
+        SwapCheckAddress,
+    }
+
+    // The entry point of the library calls, `swap_main`, is compiled with the swap module but
+    // only called by the Exchange on a device: the host tests call the functions it dispatches
+    // to directly, with parameters they build, so the readers of the Exchange parameters and of
+    // the results below are never reached.
+    pub fn get_command(_arg0: u32) -> LibCallCommand {
+        unreachable!("library calls are only made on a device")
+    }
+
+    pub mod string {
+        use arrayvec::ArrayString;
+        use core::fmt::Write;
+
+        /// The decimal formatting of the SDK is not reimplemented on the host: the amount is
+        /// written as an integer followed by its number of decimals, e.g. `1500000000e-9`, so
+        /// that the tests check the amount and decimals passed by the application, not the SDK.
+        /// Only amounts that fit in 128 bits are supported.
+        pub fn uint256_to_float(amount: &[u8; 32], decimals: usize) -> ArrayString<100> {
+            assert!(amount[..16].iter().all(|&b| b == 0), "amount over 128 bits");
+            let value = u128::from_be_bytes(amount[16..].try_into().unwrap());
+            let mut result = ArrayString::new();
+            let _ = write!(result, "{}e-{}", value, decimals);
+            result
+        }
+    }
+
+    pub mod swap {
+        use alloc::string::String;
+
+        use super::SwapAppErrorCodeTrait;
+
+        pub const DPATH_STAGE_SIZE: usize = 16;
+        pub const ADDRESS_BUF_SIZE: usize = 64;
+        pub const ADDRESS_EXTRA_ID_BUF_SIZE: usize = 32;
+        pub const AMOUNT_BUF_SIZE: usize = 16;
+        pub const COIN_CONFIG_BUF_SIZE: usize = 16;
+
+        /// Common part of a swap error code.
+        #[repr(u8)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum SwapErrorCommonCode {
+            ErrorInternal = 0x00,
+            ErrorWrongAmount = 0x01,
+            ErrorWrongDestination = 0x02,
+            ErrorWrongFees = 0x03,
+            ErrorWrongMethod = 0x04,
+            ErrorCrosschainWrongMode = 0x05,
+            ErrorCrosschainWrongMethod = 0x06,
+            ErrorCrosschainWrongHash = 0x07,
+            ErrorGeneric = 0xFF,
+        }
+
+        /// Swap error, returned to the Exchange in the response data.
+        #[derive(Debug)]
+        pub struct SwapError<T: SwapAppErrorCodeTrait> {
+            pub common_code: SwapErrorCommonCode,
+            pub app_code: T,
+            pub message: Option<String>,
+        }
+
+        impl<T: SwapAppErrorCodeTrait> SwapError<T> {
+            pub fn with_message(
+                common_code: SwapErrorCommonCode,
+                app_code: T,
+                message: String,
+            ) -> SwapError<T> {
+                SwapError {
+                    common_code,
+                    app_code,
+                    message: Some(message),
+                }
+            }
+
+            pub fn without_message(common_code: SwapErrorCommonCode, app_code: T) -> SwapError<T> {
+                SwapError {
+                    common_code,
+                    app_code,
+                    message: None,
+                }
+            }
+        }
+
+        pub struct CheckAddressParams {
+            pub coin_config: [u8; COIN_CONFIG_BUF_SIZE],
+            pub coin_config_len: usize,
+            pub dpath: [u8; DPATH_STAGE_SIZE * 4],
+            pub dpath_len: usize,
+            pub ref_address: [u8; ADDRESS_BUF_SIZE],
+            pub ref_address_len: usize,
+            pub result: i32,
+        }
+
+        impl Default for CheckAddressParams {
+            fn default() -> Self {
+                CheckAddressParams {
+                    coin_config: [0; COIN_CONFIG_BUF_SIZE],
+                    coin_config_len: 0,
+                    dpath: [0; DPATH_STAGE_SIZE * 4],
+                    dpath_len: 0,
+                    ref_address: [0; ADDRESS_BUF_SIZE],
+                    ref_address_len: 0,
+                    result: 0,
+                }
+            }
+        }
+
+        pub struct PrintableAmountParams {
+            pub coin_config: [u8; COIN_CONFIG_BUF_SIZE],
+            pub coin_config_len: usize,
+            pub amount: [u8; AMOUNT_BUF_SIZE],
+            pub amount_len: usize,
+            pub is_fee: bool,
+        }
+
+        impl Default for PrintableAmountParams {
+            fn default() -> Self {
+                PrintableAmountParams {
+                    coin_config: [0; COIN_CONFIG_BUF_SIZE],
+                    coin_config_len: 0,
+                    amount: [0; AMOUNT_BUF_SIZE],
+                    amount_len: 0,
+                    is_fee: false,
+                }
+            }
+        }
+
+        pub struct CreateTxParams {
+            pub amount: [u8; AMOUNT_BUF_SIZE],
+            pub amount_len: usize,
+            pub fee_amount: [u8; AMOUNT_BUF_SIZE],
+            pub fee_amount_len: usize,
+            pub coin_config: [u8; COIN_CONFIG_BUF_SIZE],
+            pub coin_config_len: usize,
+            pub dest_address: [u8; ADDRESS_BUF_SIZE],
+            pub dest_address_len: usize,
+            pub dest_address_extra_id: [u8; ADDRESS_EXTRA_ID_BUF_SIZE],
+            pub dest_address_extra_id_len: usize,
+            pub result: u8,
+        }
+
+        impl Default for CreateTxParams {
+            fn default() -> Self {
+                CreateTxParams {
+                    amount: [0; AMOUNT_BUF_SIZE],
+                    amount_len: 0,
+                    fee_amount: [0; AMOUNT_BUF_SIZE],
+                    fee_amount_len: 0,
+                    coin_config: [0; COIN_CONFIG_BUF_SIZE],
+                    coin_config_len: 0,
+                    dest_address: [0; ADDRESS_BUF_SIZE],
+                    dest_address_len: 0,
+                    dest_address_extra_id: [0; ADDRESS_EXTRA_ID_BUF_SIZE],
+                    dest_address_extra_id_len: 0,
+                    result: 0,
+                }
+            }
+        }
+
+        pub enum SwapResult<'a> {
+            CheckAddressResult(&'a mut CheckAddressParams, i32),
+            PrintableAmountResult(&'a mut PrintableAmountParams, &'a str),
+            CreateTxResult(&'a mut CreateTxParams, u8),
+        }
+
+        // Only called by `swap_main`, see `libcall::get_command`
+        pub fn get_check_address_params(_arg0: u32) -> CheckAddressParams {
+            unreachable!("library calls are only made on a device")
+        }
+
+        pub fn get_printable_amount_params(_arg0: u32) -> PrintableAmountParams {
+            unreachable!("library calls are only made on a device")
+        }
+
+        pub fn sign_tx_params(_arg0: u32) -> CreateTxParams {
+            unreachable!("library calls are only made on a device")
+        }
+
+        pub fn swap_return(_result: SwapResult) {
+            unreachable!("library calls are only made on a device")
+        }
+    }
+}
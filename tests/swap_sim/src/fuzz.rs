@@ -0,0 +1,135 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust - Swap simulation.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+//! Bodies of the fuzz targets of the `fuzz` directory.
+//!
+//! They are also run on the seed corpora and on the inputs of past crashes by the
+//! `fuzz_regressions` tests, so that the parsers are checked by `cargo test` too.
+
+use ledger_device_sdk::io::ApduHeader;
+use ledger_device_sdk::libcall::swap::CreateTxParams;
+
+use crate::instruction::Instruction;
+use crate::tx::Tx;
+use crate::utils::Bip32Path;
+
+/// Names of the fuzz targets.
+pub const TARGETS: [&str; 4] = ["tx", "bip32_path", "instruction", "check_swap_params"];
+
+/// Runs the body of the fuzz target `target` on `data`.
+pub fn run(target: &str, data: &[u8]) {
+    match target {
+        "tx" => tx(data),
+        "bip32_path" => bip32_path(data),
+        "instruction" => instruction(data),
+        "check_swap_params" => check_swap_params(data),
+        _ => panic!("unknown fuzz target {}", target),
+    }
+}
+
+/// Parses a transaction, as the last chunk of SIGN_TX does.
+pub fn tx(data: &[u8]) {
+    if let Ok(tx) = Tx::parse(data) {
+        let _ = tx.token_address();
+    }
+}
+
+/// Parses a derivation path, as the first chunk of SIGN_TX and GET_PUBLIC_KEY do.
+pub fn bip32_path(data: &[u8]) {
+    if let Ok(path) = Bip32Path::try_from(data) {
+        let _ = path.to_string();
+    }
+}
+
+/// Decodes an APDU header (CLA, INS, P1, P2) into an [`Instruction`].
+pub fn instruction(data: &[u8]) {
+    if let [cla, ins, p1, p2, ..] = *data {
+        let _ = Instruction::try_from(ApduHeader { cla, ins, p1, p2 });
+    }
+}
+
+/// Validates a transaction against swap parameters.
+///
+/// # Format
+///
+/// - Derivation path, in the APDU format of [`Bip32Path`]
+/// - 16 bytes: Amount buffer
+/// - 1 byte: Amount length
+/// - 16 bytes: Fee amount buffer
+/// - 1 byte: Fee amount length
+/// - 1 byte: Destination length, followed by as many bytes (copied up to the buffer size)
+/// - 1 byte: Destination extra id length, followed by as many bytes (copied up to the buffer
+///   size)
+/// - Remaining bytes: JSON transaction
+///
+/// Lengths are not checked against the buffer sizes, as the Exchange may send any value.
+pub fn check_swap_params(data: &[u8]) {
+    let mut reader = Reader(data);
+    let Some(path) = reader.path() else {
+        return;
+    };
+    let Some(params) = reader.create_tx_params() else {
+        return;
+    };
+    if let Ok(tx) = Tx::parse(reader.0) {
+        let _ = crate::swap::check_swap_params(&params, &path, &tx, &Default::default());
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(taken)
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        self.take(1).map(|byte| byte[0])
+    }
+
+    fn path(&mut self) -> Option<Bip32Path> {
+        let len = *self.0.first()? as usize;
+        self.take(1 + len * 4)?.try_into().ok()
+    }
+
+    /// Reads a buffer and its length. The length may exceed the buffer size.
+    fn buffer(&mut self, buf: &mut [u8], fixed: bool) -> Option<usize> {
+        if fixed {
+            buf.copy_from_slice(self.take(buf.len())?);
+            return Some(self.byte()? as usize);
+        }
+        let len = self.byte()? as usize;
+        let data = self.take(len)?;
+        let copied = len.min(buf.len());
+        buf[..copied].copy_from_slice(&data[..copied]);
+        Some(len)
+    }
+
+    fn create_tx_params(&mut self) -> Option<CreateTxParams> {
+        let mut params = CreateTxParams::default();
+        params.amount_len = self.buffer(&mut params.amount, true)?;
+        params.fee_amount_len = self.buffer(&mut params.fee_amount, true)?;
+        params.dest_address_len = self.buffer(&mut params.dest_address, false)?;
+        params.dest_address_extra_id_len = self.buffer(&mut params.dest_address_extra_id, false)?;
+        Some(params)
+    }
+}
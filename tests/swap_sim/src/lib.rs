@@ -0,0 +1,140 @@
+/*****************************************************************************
+ *   Ledger App Boilerplate Rust - Swap simulation.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+//! Host-side simulation of the swap feature.
+//!
+//! The swap modules of the application are compiled for the host against the SDK subset of
+//! the `sdk` directory, so that `check_swap_params`, `check_address` and `get_printable_amount`
+//! can be run without a device, Speculos or the Exchange application. The helpers of this
+//! crate build the parameters the Exchange would send.
+//!
+//! The host build of the parsers of the application is also used by the fuzz targets, see
+//! [`fuzz`]. The string tables are included so that their length checks run on every language.
+//! The descriptors signed by the trusted authority are parsed and verified as on a device.
+
+extern crate alloc;
+
+pub mod fuzz;
+
+#[path = "../../../src/address_book.rs"]
+pub mod address_book;
+#[path = "../../../src/batch.rs"]
+pub mod batch;
+#[path = "../../../src/instruction.rs"]
+pub mod instruction;
+#[path = "../../../src/risk.rs"]
+pub mod risk;
+#[path = "../../../src/settings.rs"]
+pub mod settings;
+#[path = "../../../src/spending_policy.rs"]
+pub mod spending_policy;
+#[macro_use]
+#[path = "../../../src/status_words.rs"]
+pub mod status_words;
+#[path = "../../../src/strings.rs"]
+pub mod strings;
+#[path = "../../../src/swap.rs"]
+pub mod swap;
+#[path = "../../../src/token.rs"]
+pub mod token;
+#[path = "../../../src/trusted_name.rs"]
+pub mod trusted_name;
+#[path = "../../../src/tx.rs"]
+pub mod tx;
+#[path = "../../../src/utils.rs"]
+pub mod utils;
+
+use ledger_device_sdk::libcall::swap::{CheckAddressParams, CreateTxParams, PrintableAmountParams};
+
+use crate::tx::Tx;
+use crate::utils::get_address_hash_from_pubkey;
+use ledger_device_sdk::ecc::{Secp256k1, SeedDerive};
+
+pub use crate::status_words::AppSW;
+
+/// Source of the Python module of the status words, as generated by the `build.rs` of the
+/// application.
+pub const PYTHON_STATUS_WORDS: &str = status_words!(python_client);
+
+/// Signing in swap mode goes through the APDU loop of the application, which is not simulated:
+/// no transaction is ever signed.
+pub fn normal_main(_swap_params: Option<&CreateTxParams>) -> bool {
+    false
+}
+
+/// Derivation path of the account used in the swap tests (m/44'/1'/0'/0/0).
+pub const BOL_PATH: [u32; 5] = [0x8000_002C, 0x8000_0001, 0x8000_0000, 0, 0];
+
+/// Copies `data` at the start of `buf` and returns its length.
+fn fill(buf: &mut [u8], data: &[u8]) -> usize {
+    buf[..data.len()].copy_from_slice(data);
+    data.len()
+}
+
+/// Encodes an amount the way the Exchange does: big-endian, right aligned in the buffer.
+/// Returns the buffer and the number of significant bytes.
+pub fn encode_amount(amount: u128) -> ([u8; 16], usize) {
+    let bytes = amount.to_be_bytes();
+    let len = bytes.iter().position(|&b| b != 0).map_or(0, |i| 16 - i);
+    (bytes, len)
+}
+
+/// Builds the parameters of a SIGN_TRANSACTION call.
+pub fn create_tx_params(amount: u128, fees: u128, destination: &str, memo: &str) -> CreateTxParams {
+    let mut params = CreateTxParams::default();
+    (params.amount, params.amount_len) = encode_amount(amount);
+    (params.fee_amount, params.fee_amount_len) = encode_amount(fees);
+    params.dest_address_len = fill(&mut params.dest_address, destination.as_bytes());
+    params.dest_address_extra_id_len = fill(&mut params.dest_address_extra_id, memo.as_bytes());
+    params
+}
+
+/// Builds the parameters of a CHECK_ADDRESS call.
+pub fn check_address_params(path: &[u32], address: &str) -> CheckAddressParams {
+    let mut params = CheckAddressParams::default();
+    for (i, component) in path.iter().enumerate() {
+        params.dpath[i * 4..i * 4 + 4].copy_from_slice(&component.to_be_bytes());
+    }
+    params.dpath_len = path.len();
+    params.ref_address_len = fill(&mut params.ref_address, address.as_bytes());
+    params
+}
+
+/// Builds the parameters of a GET_PRINTABLE_AMOUNT call.
+pub fn printable_amount_params(
+    amount: u128,
+    coin_config: &[u8],
+    is_fee: bool,
+) -> PrintableAmountParams {
+    let mut params = PrintableAmountParams::default();
+    (params.amount, params.amount_len) = encode_amount(amount);
+    params.coin_config_len = fill(&mut params.coin_config, coin_config);
+    params.is_fee = is_fee;
+    params
+}
+
+/// Returns the lowercase hex address of the simulated account at `path`.
+pub fn derived_address(path: &[u32]) -> String {
+    let (key, _) = Secp256k1::derive_from(path);
+    let pubkey = key.public_key().unwrap().pubkey;
+    hex::encode(&get_address_hash_from_pubkey(&pubkey)[12..])
+}
+
+/// Parses a JSON transaction, as sent with SIGN_TX.
+pub fn parse_tx(json: &str) -> Tx<'_> {
+    Tx::parse(json.as_bytes()).unwrap()
+}
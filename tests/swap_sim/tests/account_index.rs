@@ -0,0 +1,32 @@
+//! Account index of the signing path, shown in the "From" field of the review.
+
+use swap_sim::utils::Bip32Path;
+
+fn bip32_path(path: &[u32]) -> Bip32Path {
+    let mut data = vec![path.len() as u8];
+    for component in path {
+        data.extend_from_slice(&component.to_be_bytes());
+    }
+    data.as_slice().try_into().unwrap()
+}
+
+#[test]
+fn account_index_of_standard_paths() {
+    #[rustfmt::skip]
+    let cases: &[(&[u32], Option<u32>)] = &[
+        (&[0x8000_002C, 0x8000_0001, 0x8000_0000, 0, 0], Some(0)),
+        (&[0x8000_002C, 0x8000_0001, 0x8000_0007, 0, 0], Some(7)),
+        // Not hardened account
+        (&[0x8000_002C, 0x8000_0001, 7, 0, 0], None),
+        // Other address index, change or coin type
+        (&[0x8000_002C, 0x8000_0001, 0x8000_0000, 0, 1], None),
+        (&[0x8000_002C, 0x8000_0001, 0x8000_0000, 1, 0], None),
+        (&[0x8000_002C, 0x8000_003C, 0x8000_0000, 0, 0], None),
+        // Other depth
+        (&[0x8000_002C, 0x8000_0001, 0x8000_0000], None),
+        (&[], None),
+    ];
+    for (path, account) in cases {
+        assert_eq!(bip32_path(path).account_index(), *account, "{:x?}", path);
+    }
+}
@@ -0,0 +1,44 @@
+//! Entries of the address book. The book and the settings are kept in the NVM of the stub SDK,
+//! shared by the whole test binary, so a single test covers them in order.
+
+use swap_sim::address_book::{AddressBook, Contact, ADDRESS_BOOK_ENTRIES};
+use swap_sim::settings::{Settings, SETTING_ADDRESS_BOOK};
+use swap_sim::AppSW;
+
+fn contact(address_byte: u8, label: &str) -> Contact {
+    let mut data = vec![address_byte; 20];
+    data.push(label.len() as u8);
+    data.extend_from_slice(label.as_bytes());
+    data.as_slice().try_into().unwrap()
+}
+
+#[test]
+fn address_book_slots() {
+    let alice = contact(1, "Alice");
+    assert!(AddressBook.slot(&alice) == Err(AppSW::AddressBookDisabled));
+
+    Settings.set_element(SETTING_ADDRESS_BOOK, 1);
+    assert_eq!(AddressBook.slot(&alice), Ok(0));
+    AddressBook.add(&alice).unwrap();
+    assert_eq!(AddressBook.find(&[1; 20]), Some("Alice"));
+
+    // A label is saved once, whatever the address
+    assert!(AddressBook.slot(&alice) == Err(AppSW::AddressBookDuplicate));
+    assert!(AddressBook.slot(&contact(2, "Alice")) == Err(AppSW::AddressBookDuplicate));
+
+    // The label of a saved address is replaced in place
+    assert_eq!(AddressBook.slot(&contact(1, "Alice 2")), Ok(0));
+    AddressBook.add(&contact(1, "Alice 2")).unwrap();
+    assert_eq!(AddressBook.find(&[1; 20]), Some("Alice 2"));
+
+    for index in 1..ADDRESS_BOOK_ENTRIES {
+        AddressBook
+            .add(&contact(index as u8 + 1, &format!("Contact {}", index)))
+            .unwrap();
+    }
+    assert!(AddressBook.slot(&contact(0xFF, "Bob")) == Err(AppSW::AddressBookFull));
+    assert_eq!(AddressBook.slot(&contact(1, "Alice")), Ok(0));
+
+    AddressBook.remove(&[1; 20]).unwrap();
+    assert_eq!(AddressBook.slot(&contact(0xFF, "Bob")), Ok(0));
+}
@@ -0,0 +1,142 @@
+//! Checks made on each transaction added to a batch.
+
+use swap_sim::batch::{Batch, MAX_BATCH_SIZE, MAX_BATCH_TOTAL};
+use swap_sim::AppSW;
+
+const DESTINATION: &str = "de0b295669a9fd93d5f28d9ec85e40f4cb697bae";
+/// Address of the signing key, which is not the destination of the transactions.
+const FROM: [u8; 20] = [0x11; 20];
+
+fn tx(nonce: u64, coin: &str, value: u64) -> Vec<u8> {
+    format!(
+        r#"{{"nonce":{},"coin":"{}","value":{},"to":"{}","memo":""}}"#,
+        nonce, coin, value, DESTINATION
+    )
+    .into_bytes()
+}
+
+fn started_batch() -> Batch {
+    let mut batch = Batch::default();
+    let path: &[u8] = &[
+        5, 0x80, 0, 0, 44, 0x80, 0, 0, 1, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+    batch.start(path.try_into().unwrap());
+    batch
+}
+
+#[test]
+fn batch_totals() {
+    let mut batch = started_batch();
+    batch.add(&tx(1, "CRAB", 100), &FROM).unwrap();
+    batch.add(&tx(2, "CRAB", 200), &FROM).unwrap();
+    let with_fee = br#"{"nonce":3,"coin":"CRAB","value":300,"fee":7,"to":"de0b295669a9fd93d5f28d9ec85e40f4cb697bae","memo":""}"#;
+    batch.add(with_fee, &FROM).unwrap();
+    assert_eq!(batch.count(), 3);
+    assert_eq!(batch.total(), 600);
+    let [total] = batch.totals() else {
+        panic!("one coin expected");
+    };
+    assert_eq!(
+        (total.coin(), total.value(), total.fees()),
+        ("CRAB", 600, 7)
+    );
+    let nonces: Vec<u64> = batch.txs().iter().map(|tx| tx.nonce()).collect();
+    assert_eq!(nonces, [1, 2, 3]);
+}
+
+#[test]
+fn batch_not_started() {
+    let mut batch = Batch::default();
+    assert_eq!(batch.add(&tx(1, "CRAB", 100), &FROM), Err(AppSW::BadState));
+}
+
+#[test]
+fn batch_too_large() {
+    let mut batch = started_batch();
+    for nonce in 0..MAX_BATCH_SIZE as u64 {
+        batch.add(&tx(nonce, "CRAB", 1), &FROM).unwrap();
+    }
+    assert_eq!(
+        batch.add(&tx(MAX_BATCH_SIZE as u64, "CRAB", 1), &FROM),
+        Err(AppSW::BatchTooLarge)
+    );
+}
+
+#[test]
+fn batch_total_cap() {
+    let mut batch = started_batch();
+    batch
+        .add(&tx(1, "CRAB", MAX_BATCH_TOTAL - 1), &FROM)
+        .unwrap();
+    batch.add(&tx(2, "CRAB", 1), &FROM).unwrap();
+    assert_eq!(
+        batch.add(&tx(3, "CRAB", 1), &FROM),
+        Err(AppSW::BatchValueTooHigh)
+    );
+    assert_eq!(
+        batch.add(&tx(3, "CRAB", u64::MAX), &FROM),
+        Err(AppSW::BatchValueTooHigh)
+    );
+    assert_eq!(batch.count(), 2);
+    assert_eq!(batch.total(), MAX_BATCH_TOTAL);
+}
+
+#[test]
+fn batch_totals_per_coin() {
+    let mut batch = started_batch();
+    batch.add(&tx(1, "CRAB", 100), &FROM).unwrap();
+    let with_fee = br#"{"nonce":2,"coin":"ETH","value":50,"fee":3,"to":"de0b295669a9fd93d5f28d9ec85e40f4cb697bae","memo":"Rent"}"#;
+    batch.add(with_fee, &FROM).unwrap();
+    batch.add(&tx(3, "CRAB", 200), &FROM).unwrap();
+    let totals: Vec<(&str, u64, u64)> = batch
+        .totals()
+        .iter()
+        .map(|total| (total.coin(), total.value(), total.fees()))
+        .collect();
+    assert_eq!(totals, [("CRAB", 300, 0), ("ETH", 50, 3)]);
+    // The total value caps the batch whatever the coin
+    assert_eq!(batch.total(), 350);
+    assert_eq!(batch.txs()[1].memo(), "Rent");
+    assert_eq!(hex::encode(batch.txs()[1].to()), DESTINATION);
+}
+
+#[test]
+fn batch_long_ticker() {
+    let mut batch = started_batch();
+    assert_eq!(
+        batch.add(&tx(1, "CRABCRABCRAB", 100), &FROM),
+        Err(AppSW::DisplayAmountFail)
+    );
+    assert_eq!(batch.count(), 0);
+}
+
+#[test]
+fn batch_duplicate_nonce() {
+    let mut batch = started_batch();
+    batch.add(&tx(1, "CRAB", 100), &FROM).unwrap();
+    batch.add(&tx(2, "CRAB", 100), &FROM).unwrap();
+    assert_eq!(
+        batch.add(&tx(1, "CRAB", 300), &FROM),
+        Err(AppSW::BatchDuplicateNonce)
+    );
+    assert_eq!(batch.total(), 200);
+}
+
+#[test]
+fn batch_token_transfer() {
+    let mut batch = started_batch();
+    let token_tx = br#"{"nonce":1,"coin":"USDC","value":1,"to":"de0b295669a9fd93d5f28d9ec85e40f4cb697bae","memo":"","token":"9fc3da866e7df3a1c57ade1a97c9f00a70f010c8"}"#;
+    assert_eq!(batch.add(token_tx, &FROM), Err(AppSW::BatchTokenTransfer));
+}
+
+#[test]
+fn batch_risks() {
+    let mut batch = started_batch();
+    batch.add(&tx(1, "CRAB", 100), &FROM).unwrap();
+    assert_eq!(batch.risks().bits(), 0);
+    let self_transfer = br#"{"nonce":2,"coin":"CRAB","value":100,"to":"1111111111111111111111111111111111111111","memo":""}"#;
+    batch.add(self_transfer, &FROM).unwrap();
+    batch.add(&tx(3, "CRAB", 0), &FROM).unwrap();
+    // Self transfer and zero value
+    assert_eq!(batch.risks().bits(), 0x03);
+}
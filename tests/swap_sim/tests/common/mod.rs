@@ -0,0 +1,19 @@
+//! Helpers shared by the tests.
+
+use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+use sha3::{Digest, Keccak256};
+
+/// Private part of the Ledger test key, see `tests/application_client/boilerplate_descriptor.py`.
+pub const TEST_KEY: &str = "b1ed47ef58f782e2bc4d5abe70ef66d9009c2957967017054470e0f3e10f5833";
+
+/// Appends the signature of `descriptor_type || payload` to `payload`, as the host does.
+pub fn sign(descriptor_type: u8, payload: &[u8], key: &str) -> Vec<u8> {
+    let key = SigningKey::from_slice(&hex::decode(key).unwrap()).unwrap();
+    let hash = Keccak256::new()
+        .chain_update([descriptor_type])
+        .chain_update(payload)
+        .finalize();
+    let signature: Signature = key.sign_prehash(&hash).unwrap();
+    let der = signature.to_der();
+    [payload, &[der.as_bytes().len() as u8], der.as_bytes()].concat()
+}
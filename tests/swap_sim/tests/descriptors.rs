@@ -0,0 +1,85 @@
+//! Signed descriptors, verified against the trusted key of the application.
+
+mod common;
+
+use common::{sign, TEST_KEY};
+use swap_sim::token::{TokenInfo, CHAIN_ID};
+use swap_sim::trusted_name::TrustedName;
+use swap_sim::AppSW;
+
+const TRUSTED_NAME: u8 = 0x01;
+const TOKEN: u8 = 0x02;
+const ADDRESS: &str = "de0b295669a9fd93d5f28d9ec85e40f4cb697bae";
+
+fn trusted_name_payload(name: &str) -> Vec<u8> {
+    [
+        &hex::decode(ADDRESS).unwrap(),
+        &[name.len() as u8][..],
+        name.as_bytes(),
+    ]
+    .concat()
+}
+
+fn token_payload(ticker: &str, decimals: u8) -> Vec<u8> {
+    [
+        &hex::decode(ADDRESS).unwrap(),
+        &CHAIN_ID.to_be_bytes()[..],
+        &[decimals, ticker.len() as u8],
+        ticker.as_bytes(),
+    ]
+    .concat()
+}
+
+#[test]
+fn trusted_name_signed_by_the_test_key() {
+    let descriptor = sign(TRUSTED_NAME, &trusted_name_payload("crab.eth"), TEST_KEY);
+    let trusted_name = TrustedName::try_from(descriptor.as_slice()).ok().unwrap();
+    assert_eq!(trusted_name.name(), "crab.eth");
+    assert_eq!(hex::encode(trusted_name.address()), ADDRESS);
+}
+
+#[test]
+fn token_signed_by_the_test_key() {
+    let descriptor = sign(TOKEN, &token_payload("USDT", 6), TEST_KEY);
+    let token = TokenInfo::try_from(descriptor.as_slice()).ok().unwrap();
+    assert_eq!(token.ticker(), "USDT");
+    assert_eq!(token.decimals(), 6);
+}
+
+#[test]
+fn descriptor_of_another_type_is_rejected() {
+    let descriptor = sign(TOKEN, &trusted_name_payload("crab.eth"), TEST_KEY);
+    assert!(matches!(
+        TrustedName::try_from(descriptor.as_slice()),
+        Err(AppSW::TrustedNameSignatureFail)
+    ));
+
+    let descriptor = sign(TRUSTED_NAME, &token_payload("USDT", 6), TEST_KEY);
+    assert!(matches!(
+        TokenInfo::try_from(descriptor.as_slice()),
+        Err(AppSW::TokenInfoSignatureFail)
+    ));
+}
+
+#[test]
+fn descriptor_signed_by_another_key_is_rejected() {
+    let descriptor = sign(
+        TRUSTED_NAME,
+        &trusted_name_payload("crab.eth"),
+        &"01".repeat(32),
+    );
+    assert!(matches!(
+        TrustedName::try_from(descriptor.as_slice()),
+        Err(AppSW::TrustedNameSignatureFail)
+    ));
+}
+
+#[test]
+fn tampered_descriptor_is_rejected() {
+    let mut descriptor = sign(TRUSTED_NAME, &trusted_name_payload("crab.eth"), TEST_KEY);
+    descriptor[21] = b'k';
+    assert!(matches!(
+        TrustedName::try_from(descriptor.as_slice()),
+        Err(AppSW::TrustedNameSignatureFail)
+    ));
+}
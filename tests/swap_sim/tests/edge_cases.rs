@@ -0,0 +1,383 @@
+//! Edge cases of the swap validation, run against simulated Exchange parameters.
+
+mod common;
+
+use common::{sign, TEST_KEY};
+use ledger_device_sdk::libcall::swap::SwapErrorCommonCode;
+use swap_sim::swap::{check_address, check_swap_params, get_printable_amount, SwapAppErrorCode};
+use swap_sim::token::{TokenInfo, TokenRegistry, CHAIN_ID};
+use swap_sim::utils::Bip32Path;
+use swap_sim::{
+    check_address_params, create_tx_params, derived_address, parse_tx, printable_amount_params,
+    BOL_PATH,
+};
+
+/// (name, tx, path, amount, fees, destination, memo, expected error)
+type SwapCase<'a> = (
+    &'a str,
+    &'a str,
+    &'a [u32],
+    u128,
+    u128,
+    &'a str,
+    &'a str,
+    Option<SwapAppErrorCode>,
+);
+
+/// (name, tx, coin configuration, verified token ticker and decimals, expected error)
+type TokenSwapCase<'a> = (
+    &'a str,
+    &'a str,
+    &'a [u8],
+    Option<(&'a str, u8)>,
+    Option<SwapAppErrorCode>,
+);
+
+const DESTINATION: &str = "de0b295669a9fd93d5f28d9ec85e40f4cb697bae";
+const TOKEN_ADDRESS: &str = "9fc3da866e7df3a1c57ade1a97c9f00a70f010c8";
+
+fn bip32_path(path: &[u32]) -> Bip32Path {
+    let mut data = vec![path.len() as u8];
+    for component in path {
+        data.extend_from_slice(&component.to_be_bytes());
+    }
+    data.as_slice().try_into().unwrap()
+}
+
+#[test]
+fn check_swap_params_cases() {
+    let valid_tx = format!(
+        r#"{{"nonce":1,"coin":"CRAB","value":1000,"to":"{}","memo":""}}"#,
+        DESTINATION
+    );
+    let memo_tx = format!(
+        r#"{{"nonce":1,"coin":"CRAB","value":1000,"to":"{}","memo":"12345"}}"#,
+        DESTINATION
+    );
+    let fee_tx = format!(
+        r#"{{"nonce":1,"coin":"CRAB","value":1000,"fee":10,"to":"{}","memo":""}}"#,
+        DESTINATION
+    );
+    let zero_tx = format!(
+        r#"{{"nonce":1,"coin":"CRAB","value":0,"to":"{}","memo":""}}"#,
+        DESTINATION
+    );
+    let other_coin_tx = format!(
+        r#"{{"nonce":1,"coin":"ETH","value":1000,"to":"{}","memo":""}}"#,
+        DESTINATION
+    );
+    let token_tx = format!(
+        r#"{{"nonce":1,"coin":"CRAB","value":1000,"to":"{}","memo":"","token":"{}"}}"#,
+        DESTINATION, DESTINATION
+    );
+    let prefixed_destination = format!("0x{}", DESTINATION);
+    let long_path = [0x8000_002C, 0x8000_0001, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    let other_path = [0x8000_002C, 0x8000_003C, 0x8000_0000, 0, 0];
+
+    #[rustfmt::skip]
+    let cases: &[SwapCase] = &[
+        ("valid", &valid_tx, &BOL_PATH, 1000, 0, DESTINATION, "", None),
+        ("valid, 0x prefix", &valid_tx, &BOL_PATH, 1000, 0, &prefixed_destination, "", None),
+        ("valid, memo", &memo_tx, &BOL_PATH, 1000, 0, DESTINATION, "12345", None),
+        ("zero amount", &zero_tx, &BOL_PATH, 0, 0, DESTINATION, "", None),
+        ("wrong amount", &valid_tx, &BOL_PATH, 999, 0, DESTINATION, "", Some(SwapAppErrorCode::AmountMismatch)),
+        ("zero amount in swap", &valid_tx, &BOL_PATH, 0, 0, DESTINATION, "", Some(SwapAppErrorCode::AmountMismatch)),
+        ("amount over u64", &valid_tx, &BOL_PATH, u64::MAX as u128 + 1, 0, DESTINATION, "", Some(SwapAppErrorCode::AmountCastFail)),
+        ("valid, fees", &fee_tx, &BOL_PATH, 1000, 10, DESTINATION, "", None),
+        ("missing fees", &valid_tx, &BOL_PATH, 1000, 1, DESTINATION, "", Some(SwapAppErrorCode::FeeMismatch)),
+        ("wrong fees", &fee_tx, &BOL_PATH, 1000, 11, DESTINATION, "", Some(SwapAppErrorCode::FeeMismatch)),
+        ("unexpected fees", &fee_tx, &BOL_PATH, 1000, 0, DESTINATION, "", Some(SwapAppErrorCode::FeeMismatch)),
+        ("bad hex destination", &valid_tx, &BOL_PATH, 1000, 0, "de0b295669a9fd93d5f28d9ec85e40f4cb697bzz", "", Some(SwapAppErrorCode::DestinationDecodeFail)),
+        ("short destination", &valid_tx, &BOL_PATH, 1000, 0, "de0b2956", "", Some(SwapAppErrorCode::DestinationDecodeFail)),
+        ("empty destination", &valid_tx, &BOL_PATH, 1000, 0, "", "", Some(SwapAppErrorCode::DestinationDecodeFail)),
+        ("wrong destination", &valid_tx, &BOL_PATH, 1000, 0, "9fc3da866e7df3a1c57ade1a97c9f00a70f010c8", "", Some(SwapAppErrorCode::DestinationMismatch)),
+        ("missing memo", &valid_tx, &BOL_PATH, 1000, 0, DESTINATION, "12345", Some(SwapAppErrorCode::MemoMismatch)),
+        ("unexpected memo", &memo_tx, &BOL_PATH, 1000, 0, DESTINATION, "", Some(SwapAppErrorCode::MemoMismatch)),
+        ("other coin", &other_coin_tx, &BOL_PATH, 1000, 0, DESTINATION, "", Some(SwapAppErrorCode::WrongTxType)),
+        ("token transfer", &token_tx, &BOL_PATH, 1000, 0, DESTINATION, "", Some(SwapAppErrorCode::WrongTxType)),
+        ("long path", &valid_tx, &long_path, 1000, 0, DESTINATION, "", Some(SwapAppErrorCode::PathRejected)),
+        ("other app path", &valid_tx, &other_path, 1000, 0, DESTINATION, "", Some(SwapAppErrorCode::PathRejected)),
+        ("empty path", &valid_tx, &[], 1000, 0, DESTINATION, "", Some(SwapAppErrorCode::PathRejected)),
+    ];
+
+    for (name, tx, path, amount, fees, destination, memo, expected) in cases {
+        let params = create_tx_params(*amount, *fees, destination, memo);
+        let result = check_swap_params(
+            &params,
+            &bip32_path(path),
+            &parse_tx(tx),
+            &TokenRegistry::default(),
+        );
+        assert_eq!(
+            result.err().map(|error| error.app_code),
+            *expected,
+            "case: {}",
+            name
+        );
+    }
+}
+
+/// Registry holding a verified descriptor of the token at [`TOKEN_ADDRESS`].
+fn token_registry(ticker: &str, decimals: u8) -> TokenRegistry {
+    let payload = [
+        &hex::decode(TOKEN_ADDRESS).unwrap(),
+        &CHAIN_ID.to_be_bytes()[..],
+        &[decimals, ticker.len() as u8],
+        ticker.as_bytes(),
+    ]
+    .concat();
+    let descriptor = sign(0x02, &payload, TEST_KEY);
+    let mut tokens = TokenRegistry::default();
+    tokens.add(TokenInfo::try_from(descriptor.as_slice()).ok().unwrap());
+    tokens
+}
+
+/// Coin configuration of a token, as sent by the Exchange.
+fn token_config(ticker: &str, decimals: u8) -> Vec<u8> {
+    [&[ticker.len() as u8], ticker.as_bytes(), &[decimals]].concat()
+}
+
+#[test]
+fn check_swap_params_token() {
+    let token_tx = format!(
+        r#"{{"nonce":1,"coin":"USDT","value":1000,"to":"{}","memo":"","token":"{}"}}"#,
+        DESTINATION, TOKEN_ADDRESS
+    );
+    let bad_token_tx = format!(
+        r#"{{"nonce":1,"coin":"USDT","value":1000,"to":"{}","memo":"","token":"zz"}}"#,
+        DESTINATION
+    );
+    let native_tx = format!(
+        r#"{{"nonce":1,"coin":"CRAB","value":1000,"to":"{}","memo":""}}"#,
+        DESTINATION
+    );
+    let usdt = token_config("USDT", 6);
+    let usdc = token_config("USDC", 6);
+    let usdt_12 = token_config("USDT", 12);
+
+    #[rustfmt::skip]
+    let cases: &[TokenSwapCase] = &[
+        ("verified token", &token_tx, &usdt, Some(("USDT", 6)), None),
+        ("unverified token", &token_tx, &usdt, None, Some(SwapAppErrorCode::TokenMismatch)),
+        ("other ticker", &token_tx, &usdc, Some(("USDT", 6)), Some(SwapAppErrorCode::TokenMismatch)),
+        ("other decimals", &token_tx, &usdt_12, Some(("USDT", 6)), Some(SwapAppErrorCode::TokenMismatch)),
+        ("native transfer", &native_tx, &usdt, Some(("USDT", 6)), Some(SwapAppErrorCode::WrongTxType)),
+        ("native swap", &token_tx, &[], Some(("USDT", 6)), Some(SwapAppErrorCode::WrongTxType)),
+        ("bad token address", &bad_token_tx, &usdt, Some(("USDT", 6)), Some(SwapAppErrorCode::TxParsingFail)),
+        ("malformed configuration", &token_tx, &usdt[..3], Some(("USDT", 6)), Some(SwapAppErrorCode::CoinConfigDecodeFail)),
+    ];
+
+    for (name, tx, coin_config, token, expected) in cases {
+        let mut params = create_tx_params(1000, 0, DESTINATION, "");
+        params.coin_config[..coin_config.len()].copy_from_slice(coin_config);
+        params.coin_config_len = coin_config.len();
+        let tokens = token.map_or_else(TokenRegistry::default, |(ticker, decimals)| {
+            token_registry(ticker, decimals)
+        });
+        let result = check_swap_params(&params, &bip32_path(&BOL_PATH), &parse_tx(tx), &tokens);
+        assert_eq!(
+            result.err().map(|error| error.app_code),
+            *expected,
+            "case: {}",
+            name
+        );
+    }
+}
+
+/// In a SELL or a FUND, the destination is an account of the provider, sent as the provider
+/// formats it and never checked by CHECK_ADDRESS.
+#[test]
+fn check_swap_params_provider_account() {
+    let tx = format!(
+        r#"{{"nonce":1,"coin":"CRAB","value":1000,"to":"{}","memo":"For card 1234"}}"#,
+        DESTINATION
+    );
+    let destinations = [
+        "0xdE0B295669a9FD93d5F28D9Ec85E40f4cb697BAe".to_string(),
+        format!("0X{}", DESTINATION.to_uppercase()),
+        DESTINATION.to_uppercase(),
+    ];
+    for destination in &destinations {
+        let params = create_tx_params(1000, 0, destination, "For card 1234");
+        let result = check_swap_params(
+            &params,
+            &bip32_path(&BOL_PATH),
+            &parse_tx(&tx),
+            &TokenRegistry::default(),
+        );
+        assert!(result.is_ok(), "destination: {}", destination);
+    }
+
+    // The provider account is not one of the addresses of the device
+    assert_ne!(derived_address(&BOL_PATH), DESTINATION);
+
+    // A prefix alone is not an address
+    let params = create_tx_params(1000, 0, "0X", "For card 1234");
+    let error = check_swap_params(
+        &params,
+        &bip32_path(&BOL_PATH),
+        &parse_tx(&tx),
+        &TokenRegistry::default(),
+    )
+    .unwrap_err();
+    assert_eq!(error.app_code, SwapAppErrorCode::DestinationDecodeFail);
+}
+
+#[test]
+fn check_swap_params_bad_lengths() {
+    let tx = format!(
+        r#"{{"nonce":1,"coin":"CRAB","value":1000,"to":"{}","memo":""}}"#,
+        DESTINATION
+    );
+    let tx = parse_tx(&tx);
+
+    let mut params = create_tx_params(1000, 0, DESTINATION, "");
+    params.dest_address_len = params.dest_address.len() + 1;
+    let error = check_swap_params(
+        &params,
+        &bip32_path(&BOL_PATH),
+        &tx,
+        &TokenRegistry::default(),
+    )
+    .unwrap_err();
+    assert_eq!(error.app_code, SwapAppErrorCode::DestinationDecodeFail);
+
+    let mut params = create_tx_params(1000, 0, DESTINATION, "");
+    params.dest_address_extra_id_len = params.dest_address_extra_id.len() + 1;
+    let error = check_swap_params(
+        &params,
+        &bip32_path(&BOL_PATH),
+        &tx,
+        &TokenRegistry::default(),
+    )
+    .unwrap_err();
+    assert_eq!(error.app_code, SwapAppErrorCode::MemoDecodeFail);
+}
+
+#[test]
+fn check_swap_params_error_codes() {
+    let tx = format!(
+        r#"{{"nonce":1,"coin":"CRAB","value":1000,"to":"{}","memo":""}}"#,
+        DESTINATION
+    );
+    let params = create_tx_params(999, 0, DESTINATION, "");
+    let error = check_swap_params(
+        &params,
+        &bip32_path(&BOL_PATH),
+        &parse_tx(&tx),
+        &TokenRegistry::default(),
+    )
+    .unwrap_err();
+    assert_eq!(error.common_code, SwapErrorCommonCode::ErrorWrongAmount);
+    assert_eq!(error.message.as_deref(), Some("Amount tx 1000 != swap 999"));
+}
+
+#[test]
+fn check_address_cases() {
+    let address = derived_address(&BOL_PATH);
+    let prefixed = format!("0x{}", address);
+    let upper = address.to_uppercase();
+    let prefixed_upper = format!("0X{}", upper);
+    let other = derived_address(&[0x8000_002C, 0x8000_0001, 0x8000_0001, 0, 0]);
+    let long_path = [0x8000_002C; 11];
+
+    // (name, path, reference address, expected result)
+    let cases: &[(&str, &[u32], &str, i32)] = &[
+        ("lowercase", &BOL_PATH, &address, 1),
+        ("0x prefix", &BOL_PATH, &prefixed, 1),
+        ("uppercase", &BOL_PATH, &upper, 1),
+        ("0X prefix, uppercase", &BOL_PATH, &prefixed_upper, 1),
+        ("other account", &BOL_PATH, &other, 0),
+        ("bad hex", &BOL_PATH, "abcdabcd", 0),
+        ("truncated", &BOL_PATH, &address[..38], 0),
+        ("empty", &BOL_PATH, "", 0),
+        ("prefix only", &BOL_PATH, "0x", 0),
+        ("long path", &long_path, &address, 0),
+    ];
+
+    for (name, path, reference, expected) in cases {
+        let params = check_address_params(path, reference);
+        assert_eq!(check_address(&params), *expected, "case: {}", name);
+    }
+}
+
+#[test]
+fn check_address_bad_lengths() {
+    let address = derived_address(&BOL_PATH);
+
+    let mut params = check_address_params(&BOL_PATH, &address);
+    params.dpath_len = 16;
+    assert_eq!(check_address(&params), 0);
+
+    let mut params = check_address_params(&BOL_PATH, &address);
+    params.ref_address_len = params.ref_address.len() + 1;
+    assert_eq!(check_address(&params), 0);
+}
+
+#[test]
+fn get_printable_amount_cases() {
+    let usdc = [&[4u8][..], b"USDC", &[6]].concat();
+    let long_ticker = [&[11u8][..], b"ABCDEFGHIJK", &[6]].concat();
+
+    // The formatting of the SDK is not simulated: the expected strings give the amount with the
+    // number of decimals used to format it.
+    // (name, amount, coin config, is fee, expected)
+    let cases: &[(&str, u128, &[u8], bool, &str)] = &[
+        ("native", 1_500_000_000, &[], false, "CRAB 1500000000e-9"),
+        ("native, small", 1000, &[], false, "CRAB 1000e-9"),
+        ("zero amount", 0, &[], false, "CRAB 0e-9"),
+        (
+            "max u64 amount",
+            u64::MAX as u128,
+            &[],
+            false,
+            "CRAB 18446744073709551615e-9",
+        ),
+        ("amount too long", u128::MAX, &[], false, ""),
+        ("token", 2_000_000, &usdc, false, "USDC 2000000e-6"),
+        (
+            "token fees",
+            1_000_000_000,
+            &usdc,
+            true,
+            "CRAB 1000000000e-9",
+        ),
+        ("malformed config", 1000, &[4, b'U'], false, ""),
+        ("ticker too long", 1000, &long_ticker, false, ""),
+        (
+            "malformed config, fees",
+            1000,
+            &[4, b'U'],
+            true,
+            "CRAB 1000e-9",
+        ),
+    ];
+
+    for (name, amount, coin_config, is_fee, expected) in cases {
+        let params = printable_amount_params(*amount, coin_config, *is_fee);
+        assert_eq!(
+            get_printable_amount(&params).as_str(),
+            *expected,
+            "case: {}",
+            name
+        );
+    }
+}
+
+#[test]
+fn get_printable_amount_bad_lengths() {
+    // Zero-length amount: nothing to read, the amount is 0
+    let mut params = printable_amount_params(1000, &[], false);
+    params.amount_len = 0;
+    assert_eq!(get_printable_amount(&params).as_str(), "CRAB 0e-9");
+
+    // Amount length over the buffer size
+    let mut params = printable_amount_params(1000, &[], false);
+    params.amount_len = params.amount.len() + 1;
+    assert_eq!(get_printable_amount(&params).as_str(), "");
+
+    // Coin configuration length over the buffer size
+    let mut params = printable_amount_params(1000, &[], false);
+    params.coin_config_len = params.coin_config.len() + 1;
+    assert_eq!(get_printable_amount(&params).as_str(), "");
+}
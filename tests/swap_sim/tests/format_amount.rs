@@ -0,0 +1,35 @@
+//! Decimal formatting of token amounts, as displayed during the review of a token transfer.
+
+use swap_sim::utils::format_amount;
+
+#[test]
+fn format_amount_without_decimals() {
+    assert_eq!(format_amount(0, 0), "0");
+    assert_eq!(format_amount(1500, 0), "1500");
+    assert_eq!(format_amount(u64::MAX, 0), "18446744073709551615");
+}
+
+#[test]
+fn format_amount_with_9_decimals() {
+    assert_eq!(format_amount(0, 9), "0");
+    assert_eq!(format_amount(1, 9), "0.000000001");
+    assert_eq!(format_amount(1_234_567_890, 9), "1.23456789");
+    assert_eq!(format_amount(42_000_000_000, 9), "42");
+}
+
+#[test]
+fn format_amount_with_18_decimals() {
+    assert_eq!(format_amount(1, 18), "0.000000000000000001");
+    assert_eq!(format_amount(1_000_000_000_000_000_000, 18), "1");
+    assert_eq!(format_amount(u64::MAX, 18), "18.446744073709551615");
+}
+
+#[test]
+fn format_amount_trims_trailing_zeros() {
+    assert_eq!(format_amount(1_500_000, 6), "1.5");
+    assert_eq!(format_amount(2_000_000, 6), "2");
+    assert_eq!(format_amount(10_100, 3), "10.1");
+    assert_eq!(format_amount(100, 2), "1");
+    // Zeros of the integer part are kept
+    assert_eq!(format_amount(1_000_000, 3), "1000");
+}
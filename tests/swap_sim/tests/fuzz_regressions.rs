@@ -0,0 +1,45 @@
+//! Runs the fuzz targets on their seed corpora and on the inputs of past crashes.
+//!
+//! The inputs are read from `fuzz/corpus/<target>` and `fuzz/regressions/<target>`. Add the
+//! input of any crash found by the fuzzer to `fuzz/regressions/<target>` once it is fixed.
+
+use std::fs;
+use std::path::Path;
+
+use swap_sim::fuzz;
+
+fn run_inputs(dir: &str, target: &str) -> usize {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../fuzz")
+        .join(dir)
+        .join(target);
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return 0;
+    };
+    let mut count = 0;
+    for entry in entries {
+        let path = entry.unwrap().path();
+        let data = fs::read(&path).unwrap();
+        fuzz::run(target, &data);
+        count += 1;
+    }
+    count
+}
+
+#[test]
+fn seed_corpora() {
+    for target in fuzz::TARGETS {
+        assert!(
+            run_inputs("corpus", target) > 0,
+            "no seed corpus for fuzz target {}",
+            target
+        );
+    }
+}
+
+#[test]
+fn regressions() {
+    for target in fuzz::TARGETS {
+        run_inputs("regressions", target);
+    }
+}
@@ -0,0 +1,64 @@
+//! Parsing time cap and escaping of the untrusted memo of a transaction.
+
+use swap_sim::tx::{Tx, MAX_MEMO_LEN};
+use swap_sim::utils::{escape_non_printable, is_printable_ascii};
+use swap_sim::AppSW;
+
+const DESTINATION: &str = "de0b295669a9fd93d5f28d9ec85e40f4cb697bae";
+
+fn tx_with_memo(memo: &str) -> String {
+    format!(
+        r#"{{"nonce":1,"coin":"CRAB","value":1000,"to":"{}","memo":"{}"}}"#,
+        DESTINATION, memo
+    )
+}
+
+#[test]
+fn memo_length_is_capped() {
+    let longest = tx_with_memo(&"m".repeat(MAX_MEMO_LEN));
+    assert_eq!(
+        Tx::parse(longest.as_bytes()).unwrap().memo.len(),
+        MAX_MEMO_LEN
+    );
+
+    let too_long = tx_with_memo(&"m".repeat(MAX_MEMO_LEN + 1));
+    assert_eq!(
+        Tx::parse(too_long.as_bytes()).err(),
+        Some(AppSW::MemoTooLong)
+    );
+
+    // The cap is in bytes, not characters
+    let multibyte = tx_with_memo(&"é".repeat(MAX_MEMO_LEN / 2 + 1));
+    assert_eq!(
+        Tx::parse(multibyte.as_bytes()).err(),
+        Some(AppSW::MemoTooLong)
+    );
+
+    assert_eq!(Tx::parse(b"{}").err(), Some(AppSW::TxParsingFail));
+}
+
+#[test]
+fn printable_ascii() {
+    assert!(is_printable_ascii(""));
+    assert!(is_printable_ascii("For u EthDev ~!@#$%^&*()"));
+    assert!(!is_printable_ascii("tab\there"));
+    assert!(!is_printable_ascii("caf\u{e9}"));
+    assert!(!is_printable_ascii("\u{7f}"));
+}
+
+#[test]
+fn non_printable_characters_are_escaped() {
+    #[rustfmt::skip]
+    let cases: &[(&str, &str)] = &[
+        ("For u EthDev", "For u EthDev"),
+        ("line\nfeed", "line\\u{a}feed"),
+        ("back\\slash", "back\\u{5c}slash"),
+        // Right-to-left override, which reverses the rendering of what follows
+        ("invoice\u{202e}fdp.exe", "invoice\\u{202e}fdp.exe"),
+        ("zero\u{200b}width", "zero\\u{200b}width"),
+        ("caf\u{e9}", "caf\\u{e9}"),
+    ];
+    for (text, escaped) in cases {
+        assert_eq!(escape_non_printable(text), *escaped, "{:?}", text);
+    }
+}
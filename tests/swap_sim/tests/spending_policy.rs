@@ -0,0 +1,47 @@
+//! Rolling cumulative limit of the spending policy. The policy and the settings are kept in the
+//! NVM of the stub SDK, shared by the whole test binary, so a single test covers them in order.
+
+use swap_sim::settings::{Settings, SETTING_SPENDING_LIMITS};
+use swap_sim::spending_policy::{
+    LimitAction, LimitCheck, Policy, SpendingPolicy, CUMULATIVE_WINDOW,
+};
+
+#[test]
+fn rolling_cumulative_limit() {
+    Settings.set_element(SETTING_SPENDING_LIMITS, 1);
+    SpendingPolicy.set(Policy {
+        max_per_tx: 1000,
+        cumulative_limit: 2500,
+        action: LimitAction::Refuse,
+        high_value_threshold: u64::MAX,
+    });
+
+    assert!(SpendingPolicy.check(1001) == LimitCheck::Refuse);
+    SpendingPolicy.record(1000);
+    SpendingPolicy.record(1000);
+    assert!(SpendingPolicy.check(500) == LimitCheck::Allowed);
+    assert!(SpendingPolicy.check(501) == LimitCheck::Refuse);
+    // Transactions of a zero native value are neither checked nor counted
+    assert!(SpendingPolicy.check(0) == LimitCheck::Allowed);
+    SpendingPolicy.record(0);
+    assert!(SpendingPolicy.check(501) == LimitCheck::Refuse);
+
+    // Fill the window, the oldest value then leaves it with each new transaction
+    for _ in 0..CUMULATIVE_WINDOW - 3 {
+        SpendingPolicy.record(1);
+    }
+    assert!(SpendingPolicy.check(493) == LimitCheck::Allowed);
+    assert!(SpendingPolicy.check(494) == LimitCheck::Refuse);
+    SpendingPolicy.record(1);
+    assert!(SpendingPolicy.check(1000) == LimitCheck::Allowed);
+
+    // Setting a policy empties the window
+    SpendingPolicy.set(Policy {
+        max_per_tx: 1000,
+        cumulative_limit: 1000,
+        action: LimitAction::Warn,
+        high_value_threshold: u64::MAX,
+    });
+    assert!(SpendingPolicy.check(1000) == LimitCheck::Allowed);
+    assert!(SpendingPolicy.check(1001) == LimitCheck::Warn);
+}
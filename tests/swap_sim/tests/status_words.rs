@@ -0,0 +1,16 @@
+//! Checks that the status words of the Python client are generated from the application table.
+
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn python_client_is_up_to_date() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../application_client/boilerplate_status_words.py");
+    let source = fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        source == swap_sim::PYTHON_STATUS_WORDS,
+        "{} is out of date, build the application and replace it with the file generated in its OUT_DIR",
+        path.display()
+    );
+}
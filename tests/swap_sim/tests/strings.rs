@@ -0,0 +1,35 @@
+//! String tables of the on-device texts. Their lengths are checked at compile time, these tests
+//! cover the filling of the templates.
+
+use swap_sim::strings::{fill, EN, FR};
+
+#[test]
+fn fill_replay_text() {
+    assert_eq!(
+        fill(EN.replay_text, &[&3u64, &7u64]),
+        "Nonce 3 is not greater than\nthe last signed nonce 7."
+    );
+    assert_eq!(
+        fill(FR.replay_text, &[&3u64, &7u64]),
+        "Le nonce 3 n'est pas superieur\nau dernier nonce signe 7."
+    );
+}
+
+#[test]
+fn fill_missing_and_extra_values() {
+    assert_eq!(fill("{} and {}", &[&1]), "1 and ");
+    assert_eq!(fill("no placeholder", &[&1]), "no placeholder");
+    assert_eq!(fill("{}{}", &[&"a", &"b", &"c"]), "ab");
+}
+
+#[test]
+fn every_language_has_the_same_placeholders() {
+    assert_eq!(
+        EN.replay_text.matches("{}").count(),
+        FR.replay_text.matches("{}").count()
+    );
+    assert_eq!(
+        EN.cumulative_window.matches("{}").count(),
+        FR.cumulative_window.matches("{}").count()
+    );
+}